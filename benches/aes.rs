@@ -0,0 +1,69 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use cryptonulz::aes::{Aes128, Aes256, Backend, Cryptoprovider};
+
+const BACKENDS: [Backend; 4] = [
+    Backend::Reference,
+    Backend::TTable,
+    Backend::Bitsliced,
+    Backend::AesNi,
+];
+const BULK_LEN: usize = 16 * 1024;
+
+fn block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encrypt_block");
+    group.throughput(Throughput::Bytes(16));
+    for backend in BACKENDS {
+        let aes = Aes128::with_backend(&[0x42; 16], backend);
+        group.bench_function(BenchmarkId::new("aes128", format!("{backend:?}")), |b| {
+            let mut block = [0; 16];
+            b.iter(|| aes.encrypt_block(black_box(&mut block)))
+        });
+    }
+    group.finish();
+}
+
+fn bulk(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk_16k");
+    group.throughput(Throughput::Bytes(BULK_LEN as u64));
+    for backend in BACKENDS {
+        let aes = Aes128::with_backend(&[0x42; 16], backend);
+        let mut buffer = vec![0; BULK_LEN];
+        group.bench_function(
+            BenchmarkId::new("aes128_encrypt", format!("{backend:?}")),
+            |b| {
+                b.iter(|| {
+                    for block in buffer.chunks_exact_mut(16) {
+                        aes.encrypt_block(black_box(block.try_into().unwrap()));
+                    }
+                })
+            },
+        );
+        group.bench_function(
+            BenchmarkId::new("aes128_decrypt", format!("{backend:?}")),
+            |b| {
+                b.iter(|| {
+                    for block in buffer.chunks_exact_mut(16) {
+                        aes.decrypt_block(black_box(block.try_into().unwrap()));
+                    }
+                })
+            },
+        );
+        let aes = Aes256::with_backend(&[0x42; 32], backend);
+        group.bench_function(
+            BenchmarkId::new("aes256_encrypt", format!("{backend:?}")),
+            |b| {
+                b.iter(|| {
+                    for block in buffer.chunks_exact_mut(16) {
+                        aes.encrypt_block(black_box(block.try_into().unwrap()));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, block, bulk);
+criterion_main!(benches);
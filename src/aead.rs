@@ -0,0 +1,28 @@
+use std::fmt;
+
+pub mod ccm;
+pub mod eax;
+pub mod gcm;
+pub mod gcm_siv;
+pub mod ocb;
+pub mod siv;
+
+pub use ccm::Ccm;
+pub use eax::{Eax, EaxHeader};
+pub use gcm::Gcm;
+pub use gcm_siv::GcmSiv;
+pub use ocb::Ocb;
+pub use siv::Siv;
+
+/// Returned when a ciphertext does not authenticate under the given key,
+/// nonce and associated data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Error;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("aead authentication failed")
+    }
+}
+
+impl std::error::Error for Error {}
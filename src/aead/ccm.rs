@@ -0,0 +1,159 @@
+use crate::aead::Error;
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::util::{ct_eq, xor_in_place};
+
+/// Counter with CBC-MAC (NIST SP 800-38C, RFC 3610) on top of any
+/// [`Cryptoprovider`].
+///
+/// The nonce length N fixes the size of the length field to 15 - N bytes,
+/// so shorter nonces allow longer messages.
+pub struct Ccm<C: Cryptoprovider> {
+    cipher: C,
+    nonce_len: usize,
+    tag_len: usize,
+}
+
+impl<C: Cryptoprovider> Ccm<C> {
+    /// `nonce_len` has to be in 7..=13 and `tag_len` one of 4, 6, ..., 16.
+    pub fn new(cipher: C, nonce_len: usize, tag_len: usize) -> Self {
+        assert!(
+            (7..=13).contains(&nonce_len),
+            "CCM nonces are 7 to 13 bytes long"
+        );
+        assert!(
+            matches!(tag_len, 4 | 6 | 8 | 10 | 12 | 14 | 16),
+            "CCM tags are 4, 6, 8, 10, 12, 14 or 16 bytes long"
+        );
+        Self {
+            cipher,
+            nonce_len,
+            tag_len,
+        }
+    }
+
+    pub fn nonce_len(&self) -> usize {
+        self.nonce_len
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Largest message in bytes that fits the length field.
+    pub fn max_len(&self) -> u64 {
+        match 15 - self.nonce_len {
+            8 => u64::MAX,
+            l => (1 << (8 * l)) - 1,
+        }
+    }
+
+    /// Encrypts `buffer` in place and appends the tag.
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], buffer: &mut Vec<u8>) {
+        let tag = self.encrypt_detached(nonce, aad, buffer);
+        buffer.extend_from_slice(&tag);
+    }
+
+    /// Verifies and strips the tag at the end of `buffer` and decrypts it in
+    /// place. `buffer` is left untouched if authentication fails.
+    pub fn decrypt(&self, nonce: &[u8], aad: &[u8], buffer: &mut Vec<u8>) -> Result<(), Error> {
+        if buffer.len() < self.tag_len {
+            return Err(Error);
+        }
+        let len = buffer.len() - self.tag_len;
+        let (ciphertext, tag) = buffer.split_at_mut(len);
+        self.decrypt_detached(nonce, aad, ciphertext, tag)?;
+        buffer.truncate(len);
+        Ok(())
+    }
+
+    /// Encrypts `buffer` in place and returns the tag.
+    pub fn encrypt_detached(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8]) -> Vec<u8> {
+        assert_eq!(nonce.len(), self.nonce_len, "wrong CCM nonce length");
+        assert!(buffer.len() as u64 <= self.max_len());
+        let mut tag = self.cbc_mac(nonce, aad, buffer);
+        let mut s0 = self.counter_block(nonce, 0);
+        self.cipher.encrypt_block(&mut s0);
+        xor_in_place(&mut tag, &s0);
+        self.ctr(nonce, buffer);
+        tag[..self.tag_len].to_vec()
+    }
+
+    /// Decrypts `buffer` in place and checks `tag`. `buffer` is restored to
+    /// the ciphertext if authentication fails. A nonce of the wrong length
+    /// or an overlong `buffer` fail without touching it.
+    pub fn decrypt_detached(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), Error> {
+        if nonce.len() != self.nonce_len || buffer.len() as u64 > self.max_len() {
+            return Err(Error);
+        }
+        self.ctr(nonce, buffer);
+        let mut expected = self.cbc_mac(nonce, aad, buffer);
+        let mut s0 = self.counter_block(nonce, 0);
+        self.cipher.encrypt_block(&mut s0);
+        xor_in_place(&mut expected, &s0);
+        if !ct_eq(&expected[..self.tag_len], tag) {
+            // The MAC covers the plaintext, so the only way to not release
+            // unauthenticated data is to restore the ciphertext.
+            self.ctr(nonce, buffer);
+            return Err(Error);
+        }
+        Ok(())
+    }
+
+    fn cbc_mac(&self, nonce: &[u8], aad: &[u8], payload: &[u8]) -> [u8; BLOCKSIZE] {
+        let l = 15 - self.nonce_len;
+        let mut b0 = [0; BLOCKSIZE];
+        b0[0] = (if aad.is_empty() { 0 } else { 0x40 })
+            | (((self.tag_len - 2) / 2) << 3) as u8
+            | (l - 1) as u8;
+        b0[1..=self.nonce_len].copy_from_slice(nonce);
+        b0[1 + self.nonce_len..].copy_from_slice(&(payload.len() as u64).to_be_bytes()[8 - l..]);
+
+        let mut mac = b0;
+        self.cipher.encrypt_block(&mut mac);
+        if !aad.is_empty() {
+            let aad_len = aad.len() as u64;
+            let prefix: Vec<u8> = if aad_len < 0xff00 {
+                (aad_len as u16).to_be_bytes().to_vec()
+            } else if aad_len <= u32::MAX as u64 {
+                [&[0xff, 0xfe][..], &(aad_len as u32).to_be_bytes()].concat()
+            } else {
+                [&[0xff, 0xff][..], &aad_len.to_be_bytes()].concat()
+            };
+            let encoded = [&prefix[..], aad].concat();
+            self.absorb(&mut mac, &encoded);
+        }
+        self.absorb(&mut mac, payload);
+        mac
+    }
+
+    /// CBC-MACs `data` into `mac`, zero padding the last block.
+    fn absorb(&self, mac: &mut [u8; BLOCKSIZE], data: &[u8]) {
+        for chunk in data.chunks(BLOCKSIZE) {
+            xor_in_place(&mut mac[..chunk.len()], chunk);
+            self.cipher.encrypt_block(mac);
+        }
+    }
+
+    fn counter_block(&self, nonce: &[u8], counter: u64) -> [u8; BLOCKSIZE] {
+        let l = 15 - self.nonce_len;
+        let mut block = [0; BLOCKSIZE];
+        block[0] = (l - 1) as u8;
+        block[1..=self.nonce_len].copy_from_slice(nonce);
+        block[1 + self.nonce_len..].copy_from_slice(&counter.to_be_bytes()[8 - l..]);
+        block
+    }
+
+    fn ctr(&self, nonce: &[u8], buffer: &mut [u8]) {
+        for (i, chunk) in buffer.chunks_mut(BLOCKSIZE).enumerate() {
+            let mut keystream = self.counter_block(nonce, i as u64 + 1);
+            self.cipher.encrypt_block(&mut keystream);
+            xor_in_place(chunk, &keystream);
+        }
+    }
+}
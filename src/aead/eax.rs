@@ -0,0 +1,156 @@
+use crate::aead::Error;
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::mac::Cmac;
+use crate::util::{ct_eq, xor_in_place};
+
+/// EAX (Bellare, Rogaway and Wagner) on top of any [`Cryptoprovider`].
+///
+/// Composed from counter mode and OMAC only. Nonces and headers, EAX's name
+/// for associated data, can have any length.
+pub struct Eax<C: Cryptoprovider> {
+    omac: Cmac<C>,
+    tag_len: usize,
+}
+
+/// Header data absorbed piece by piece, see [`Eax::header`].
+pub struct EaxHeader<'a, C: Cryptoprovider> {
+    omac: Cmac<&'a C>,
+}
+
+impl<C: Cryptoprovider> EaxHeader<'_, C> {
+    pub fn update(&mut self, data: &[u8]) {
+        self.omac.update(data);
+    }
+}
+
+impl<C: Cryptoprovider> Eax<C> {
+    pub fn new(cipher: C) -> Self {
+        Self::with_tag_len(cipher, BLOCKSIZE)
+    }
+
+    /// Truncates tags to `tag_len` bytes, which has to be in 1..=16.
+    pub fn with_tag_len(cipher: C, tag_len: usize) -> Self {
+        assert!(
+            (1..=BLOCKSIZE).contains(&tag_len),
+            "EAX tags are 1 to 16 bytes long"
+        );
+        Self {
+            omac: Cmac::new(cipher),
+            tag_len,
+        }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Starts absorbing a header that is not available as one slice.
+    pub fn header(&self) -> EaxHeader<'_, C> {
+        EaxHeader {
+            omac: self.omac_t(1),
+        }
+    }
+
+    /// Encrypts `buffer` in place and appends the tag.
+    pub fn encrypt(&self, nonce: &[u8], header: &[u8], buffer: &mut Vec<u8>) {
+        let tag = self.encrypt_detached(nonce, header, buffer);
+        buffer.extend_from_slice(&tag);
+    }
+
+    /// Verifies and strips the tag at the end of `buffer` and decrypts it in
+    /// place. `buffer` is left untouched if authentication fails.
+    pub fn decrypt(&self, nonce: &[u8], header: &[u8], buffer: &mut Vec<u8>) -> Result<(), Error> {
+        if buffer.len() < self.tag_len {
+            return Err(Error);
+        }
+        let len = buffer.len() - self.tag_len;
+        let (ciphertext, tag) = buffer.split_at_mut(len);
+        self.decrypt_detached(nonce, header, ciphertext, tag)?;
+        buffer.truncate(len);
+        Ok(())
+    }
+
+    /// Encrypts `buffer` in place and returns the tag.
+    pub fn encrypt_detached(&self, nonce: &[u8], header: &[u8], buffer: &mut [u8]) -> Vec<u8> {
+        let mut h = self.header();
+        h.update(header);
+        self.encrypt_with_header(nonce, h, buffer)
+    }
+
+    /// Checks `tag` and decrypts `buffer` in place. Nothing is decrypted if
+    /// authentication fails.
+    pub fn decrypt_detached(
+        &self,
+        nonce: &[u8],
+        header: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), Error> {
+        let mut h = self.header();
+        h.update(header);
+        self.decrypt_with_header(nonce, h, buffer, tag)
+    }
+
+    /// Like [`Eax::encrypt_detached`] with an incrementally absorbed header.
+    pub fn encrypt_with_header(
+        &self,
+        nonce: &[u8],
+        header: EaxHeader<'_, C>,
+        buffer: &mut [u8],
+    ) -> Vec<u8> {
+        let n = self.omac_t_of(0, nonce);
+        self.ctr(&n, buffer);
+        self.tag(&n, header, buffer)
+    }
+
+    /// Like [`Eax::decrypt_detached`] with an incrementally absorbed header.
+    pub fn decrypt_with_header(
+        &self,
+        nonce: &[u8],
+        header: EaxHeader<'_, C>,
+        buffer: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), Error> {
+        let n = self.omac_t_of(0, nonce);
+        let expected = self.tag(&n, header, buffer);
+        if !ct_eq(&expected, tag) {
+            return Err(Error);
+        }
+        self.ctr(&n, buffer);
+        Ok(())
+    }
+
+    /// OMAC^t, an OMAC instance prefixed with the block [t]_n.
+    fn omac_t(&self, t: u8) -> Cmac<&C> {
+        let mut omac = self.omac.by_ref();
+        let mut prefix = [0; BLOCKSIZE];
+        prefix[BLOCKSIZE - 1] = t;
+        omac.update(&prefix);
+        omac
+    }
+
+    fn omac_t_of(&self, t: u8, data: &[u8]) -> [u8; BLOCKSIZE] {
+        let mut omac = self.omac_t(t);
+        omac.update(data);
+        omac.finalize_block()
+    }
+
+    fn tag(&self, n: &[u8; BLOCKSIZE], mut header: EaxHeader<'_, C>, ciphertext: &[u8]) -> Vec<u8> {
+        let mut tag = *n;
+        xor_in_place(&mut tag, &header.omac.finalize_block());
+        xor_in_place(&mut tag, &self.omac_t_of(2, ciphertext));
+        tag[..self.tag_len].to_vec()
+    }
+
+    /// Counter mode starting at N, the whole block is a counter that wraps
+    /// around modulo 2^128.
+    fn ctr(&self, n: &[u8; BLOCKSIZE], buffer: &mut [u8]) {
+        let mut counter = u128::from_be_bytes(*n);
+        for chunk in buffer.chunks_mut(BLOCKSIZE) {
+            let mut keystream = counter.to_be_bytes();
+            self.omac.cipher().encrypt_block(&mut keystream);
+            xor_in_place(chunk, &keystream);
+            counter = counter.wrapping_add(1);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::aead::Error;
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::universal_hash::{Ghash, UniversalHash};
+use crate::util::{ct_eq, xor_in_place};
+
+/// Galois/Counter Mode (NIST SP 800-38D) on top of any [`Cryptoprovider`].
+///
+/// Nonces of any non-zero length are accepted, 12 bytes is the recommended
+/// and fastest size. A nonce must never be reused under the same key.
+pub struct Gcm<C: Cryptoprovider> {
+    cipher: C,
+    h: [u8; BLOCKSIZE],
+    tag_len: usize,
+}
+
+impl<C: Cryptoprovider> Gcm<C> {
+    /// Largest plaintext in bytes, 2^39 - 256 bits.
+    pub const MAX_LEN: u64 = (1 << 36) - 32;
+
+    pub fn new(cipher: C) -> Self {
+        Self::with_tag_len(cipher, BLOCKSIZE)
+    }
+
+    /// Truncates tags to `tag_len` bytes, which has to be 4, 8 or 12..=16.
+    pub fn with_tag_len(cipher: C, tag_len: usize) -> Self {
+        assert!(
+            matches!(tag_len, 4 | 8 | 12..=16),
+            "GCM tags are 4, 8 or 12 to 16 bytes long"
+        );
+        let mut h = [0; BLOCKSIZE];
+        cipher.encrypt_block(&mut h);
+        Self { cipher, h, tag_len }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Encrypts `buffer` in place and appends the tag.
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], buffer: &mut Vec<u8>) {
+        let tag = self.encrypt_detached(nonce, aad, buffer);
+        buffer.extend_from_slice(&tag);
+    }
+
+    /// Verifies and strips the tag at the end of `buffer`, then decrypts it in
+    /// place. `buffer` is left untouched if authentication fails.
+    pub fn decrypt(&self, nonce: &[u8], aad: &[u8], buffer: &mut Vec<u8>) -> Result<(), Error> {
+        if buffer.len() < self.tag_len {
+            return Err(Error);
+        }
+        let len = buffer.len() - self.tag_len;
+        let (ciphertext, tag) = buffer.split_at_mut(len);
+        self.decrypt_detached(nonce, aad, ciphertext, tag)?;
+        buffer.truncate(len);
+        Ok(())
+    }
+
+    /// Encrypts `buffer` in place and returns the tag.
+    pub fn encrypt_detached(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8]) -> Vec<u8> {
+        assert!(!nonce.is_empty(), "GCM needs a non-empty nonce");
+        assert!(buffer.len() as u64 <= Self::MAX_LEN);
+        let j0 = self.j0(nonce);
+        self.gctr(&inc32(&j0), buffer);
+        self.tag(&j0, aad, buffer)[..self.tag_len].to_vec()
+    }
+
+    /// Checks `tag` and decrypts `buffer` in place. Nothing is decrypted if
+    /// authentication fails, an empty nonce fails authentication as well.
+    pub fn decrypt_detached(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), Error> {
+        if nonce.is_empty() || buffer.len() as u64 > Self::MAX_LEN {
+            return Err(Error);
+        }
+        let j0 = self.j0(nonce);
+        let expected = self.tag(&j0, aad, buffer);
+        if !ct_eq(&expected[..self.tag_len], tag) {
+            return Err(Error);
+        }
+        self.gctr(&inc32(&j0), buffer);
+        Ok(())
+    }
+
+    /// The pre-counter block, `nonce` has to be non-empty.
+    fn j0(&self, nonce: &[u8]) -> [u8; BLOCKSIZE] {
+        if nonce.len() == 12 {
+            let mut j0 = [0; BLOCKSIZE];
+            j0[..12].copy_from_slice(nonce);
+            j0[15] = 1;
+            return j0;
+        }
+        let mut ghash = Ghash::new(&self.h);
+        ghash.update_padded(nonce);
+        let mut lengths = [0; BLOCKSIZE];
+        lengths[8..].copy_from_slice(&(nonce.len() as u64 * 8).to_be_bytes());
+        ghash.update_block(&lengths);
+        ghash.finalize()
+    }
+
+    fn gctr(&self, icb: &[u8; BLOCKSIZE], buffer: &mut [u8]) {
+        let mut counter = *icb;
+        for chunk in buffer.chunks_mut(BLOCKSIZE) {
+            let mut keystream = counter;
+            self.cipher.encrypt_block(&mut keystream);
+            xor_in_place(chunk, &keystream);
+            counter = inc32(&counter);
+        }
+    }
+
+    fn tag(&self, j0: &[u8; BLOCKSIZE], aad: &[u8], ciphertext: &[u8]) -> [u8; BLOCKSIZE] {
+        let mut ghash = Ghash::new(&self.h);
+        ghash.update_padded(aad);
+        ghash.update_padded(ciphertext);
+        let mut lengths = [0; BLOCKSIZE];
+        lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_be_bytes());
+        lengths[8..].copy_from_slice(&(ciphertext.len() as u64 * 8).to_be_bytes());
+        ghash.update_block(&lengths);
+        let mut tag = ghash.finalize();
+        let mut mask = *j0;
+        self.cipher.encrypt_block(&mut mask);
+        xor_in_place(&mut tag, &mask);
+        tag
+    }
+}
+
+/// Increments the last 32 bits of `block` modulo 2^32.
+fn inc32(block: &[u8; BLOCKSIZE]) -> [u8; BLOCKSIZE] {
+    let mut next = *block;
+    let ctr = u32::from_be_bytes(block[12..].try_into().unwrap());
+    next[12..].copy_from_slice(&ctr.wrapping_add(1).to_be_bytes());
+    next
+}
@@ -0,0 +1,156 @@
+use crate::aead::Error;
+use crate::aes::{Aes128, Aes256, Cryptoprovider, BLOCKSIZE};
+use crate::universal_hash::{Polyval, UniversalHash};
+use crate::util::{ct_eq, xor_in_place};
+
+pub const NONCE_LEN: usize = 12;
+pub const TAG_LEN: usize = 16;
+/// Largest plaintext and associated data in bytes, 2^36.
+pub const MAX_LEN: u64 = 1 << 36;
+
+/// AES-GCM-SIV (RFC 8452), nonce misuse resistant authenticated encryption.
+///
+/// Every nonce derives fresh authentication and encryption keys from the key
+/// generating key. Repeating a nonce only reveals whether the same message was
+/// encrypted twice under the same associated data.
+pub struct GcmSiv<C: Cryptoprovider> {
+    key_generating_cipher: C,
+    encryption_key_len: usize,
+    new_cipher: fn(&[u8]) -> C,
+}
+
+impl GcmSiv<Aes128> {
+    pub fn new(key: &[u8; 16]) -> Self {
+        Self {
+            key_generating_cipher: Aes128::new(key),
+            encryption_key_len: 16,
+            new_cipher: |key| Aes128::new(key.try_into().unwrap()),
+        }
+    }
+}
+
+impl GcmSiv<Aes256> {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            key_generating_cipher: Aes256::new(key),
+            encryption_key_len: 32,
+            new_cipher: |key| Aes256::new(key.try_into().unwrap()),
+        }
+    }
+}
+
+impl<C: Cryptoprovider> GcmSiv<C> {
+    /// Encrypts `buffer` in place and appends the tag.
+    pub fn encrypt(&self, nonce: &[u8; NONCE_LEN], aad: &[u8], buffer: &mut Vec<u8>) {
+        let tag = self.encrypt_detached(nonce, aad, buffer);
+        buffer.extend_from_slice(&tag);
+    }
+
+    /// Verifies and strips the tag at the end of `buffer` and decrypts it in
+    /// place. `buffer` is left untouched if authentication fails.
+    pub fn decrypt(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        if buffer.len() < TAG_LEN {
+            return Err(Error);
+        }
+        let len = buffer.len() - TAG_LEN;
+        let (ciphertext, tag) = buffer.split_at_mut(len);
+        let tag: [u8; BLOCKSIZE] = (&*tag).try_into().unwrap();
+        self.decrypt_detached(nonce, aad, ciphertext, &tag)?;
+        buffer.truncate(len);
+        Ok(())
+    }
+
+    /// Encrypts `buffer` in place and returns the tag.
+    pub fn encrypt_detached(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> [u8; BLOCKSIZE] {
+        assert!(buffer.len() as u64 <= MAX_LEN && aad.len() as u64 <= MAX_LEN);
+        let (auth_key, cipher) = self.derive_keys(nonce);
+        let tag = self.tag(&auth_key, &cipher, nonce, aad, buffer);
+        ctr(&cipher, &tag, buffer);
+        tag
+    }
+
+    /// Decrypts `buffer` in place and checks `tag`. `buffer` is restored to
+    /// the ciphertext if authentication fails.
+    pub fn decrypt_detached(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; BLOCKSIZE],
+    ) -> Result<(), Error> {
+        if buffer.len() as u64 > MAX_LEN || aad.len() as u64 > MAX_LEN {
+            return Err(Error);
+        }
+        let (auth_key, cipher) = self.derive_keys(nonce);
+        ctr(&cipher, tag, buffer);
+        let expected = self.tag(&auth_key, &cipher, nonce, aad, buffer);
+        if !ct_eq(&expected, tag) {
+            ctr(&cipher, tag, buffer);
+            return Err(Error);
+        }
+        Ok(())
+    }
+
+    /// Derives the per nonce message authentication key and encryption
+    /// cipher, each key is built from the first halves of encrypted
+    /// `LE32(i) || nonce` blocks.
+    fn derive_keys(&self, nonce: &[u8; NONCE_LEN]) -> ([u8; BLOCKSIZE], C) {
+        let mut material = [0; BLOCKSIZE + 32];
+        let len = BLOCKSIZE + self.encryption_key_len;
+        for (i, half) in material[..len].chunks_exact_mut(8).enumerate() {
+            let mut block = [0; BLOCKSIZE];
+            block[..4].copy_from_slice(&(i as u32).to_le_bytes());
+            block[4..].copy_from_slice(nonce);
+            self.key_generating_cipher.encrypt_block(&mut block);
+            half.copy_from_slice(&block[..8]);
+        }
+        let auth_key = material[..BLOCKSIZE].try_into().unwrap();
+        (auth_key, (self.new_cipher)(&material[BLOCKSIZE..len]))
+    }
+
+    fn tag(
+        &self,
+        auth_key: &[u8; BLOCKSIZE],
+        cipher: &C,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> [u8; BLOCKSIZE] {
+        let mut polyval = Polyval::new(auth_key);
+        polyval.update_padded(aad);
+        polyval.update_padded(plaintext);
+        let mut lengths = [0; BLOCKSIZE];
+        lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_le_bytes());
+        lengths[8..].copy_from_slice(&(plaintext.len() as u64 * 8).to_le_bytes());
+        polyval.update_block(&lengths);
+        let mut s = polyval.finalize();
+        xor_in_place(&mut s[..NONCE_LEN], nonce);
+        s[15] &= 0x7f;
+        cipher.encrypt_block(&mut s);
+        s
+    }
+}
+
+/// Counter mode with the tag as initial block, the first 32 bits are a
+/// little-endian counter that wraps around.
+fn ctr<C: Cryptoprovider>(cipher: &C, tag: &[u8; BLOCKSIZE], buffer: &mut [u8]) {
+    let mut counter = *tag;
+    counter[15] |= 0x80;
+    for chunk in buffer.chunks_mut(BLOCKSIZE) {
+        let mut keystream = counter;
+        cipher.encrypt_block(&mut keystream);
+        xor_in_place(chunk, &keystream);
+        let ctr = u32::from_le_bytes(counter[..4].try_into().unwrap());
+        counter[..4].copy_from_slice(&ctr.wrapping_add(1).to_le_bytes());
+    }
+}
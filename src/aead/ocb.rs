@@ -0,0 +1,210 @@
+use crate::aead::Error;
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::util::{as_block, ct_eq, dbl, xor_in_place};
+
+/// Every block index of a `u64` sized message has at most 63 trailing zeros.
+const L_TABLE_SIZE: usize = 64;
+
+/// OCB3 (RFC 7253) on top of any [`Cryptoprovider`].
+///
+/// Needs a single block cipher call per block of plaintext. Nonces are 1 to
+/// 15 bytes long and must never repeat under the same key and tag length.
+pub struct Ocb<C: Cryptoprovider> {
+    cipher: C,
+    tag_len: usize,
+    l_star: [u8; BLOCKSIZE],
+    l_dollar: [u8; BLOCKSIZE],
+    // L_i = 2^(i + 1) * L_$, indexed by the number of trailing zeros of the
+    // block index.
+    l: [[u8; BLOCKSIZE]; L_TABLE_SIZE],
+}
+
+impl<C: Cryptoprovider> Ocb<C> {
+    pub fn new(cipher: C) -> Self {
+        Self::with_tag_len(cipher, BLOCKSIZE)
+    }
+
+    /// Truncates tags to `tag_len` bytes, which has to be in 1..=16.
+    pub fn with_tag_len(cipher: C, tag_len: usize) -> Self {
+        assert!(
+            (1..=BLOCKSIZE).contains(&tag_len),
+            "OCB tags are 1 to 16 bytes long"
+        );
+        let mut l_star = [0; BLOCKSIZE];
+        cipher.encrypt_block(&mut l_star);
+        let l_dollar = dbl(&l_star);
+        let mut l = [[0; BLOCKSIZE]; L_TABLE_SIZE];
+        l[0] = dbl(&l_dollar);
+        for i in 1..L_TABLE_SIZE {
+            l[i] = dbl(&l[i - 1]);
+        }
+        Self {
+            cipher,
+            tag_len,
+            l_star,
+            l_dollar,
+            l,
+        }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Encrypts `buffer` in place and appends the tag.
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], buffer: &mut Vec<u8>) {
+        let tag = self.encrypt_detached(nonce, aad, buffer);
+        buffer.extend_from_slice(&tag);
+    }
+
+    /// Verifies and strips the tag at the end of `buffer` and decrypts it in
+    /// place. `buffer` is left untouched if authentication fails.
+    pub fn decrypt(&self, nonce: &[u8], aad: &[u8], buffer: &mut Vec<u8>) -> Result<(), Error> {
+        if buffer.len() < self.tag_len {
+            return Err(Error);
+        }
+        let len = buffer.len() - self.tag_len;
+        let (ciphertext, tag) = buffer.split_at_mut(len);
+        self.decrypt_detached(nonce, aad, ciphertext, tag)?;
+        buffer.truncate(len);
+        Ok(())
+    }
+
+    /// Encrypts `buffer` in place and returns the tag.
+    pub fn encrypt_detached(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8]) -> Vec<u8> {
+        assert!(
+            (1..BLOCKSIZE).contains(&nonce.len()),
+            "OCB nonces are 1 to 15 bytes long"
+        );
+        let mut offset = self.initial_offset(nonce);
+        let mut checksum = [0; BLOCKSIZE];
+        let full = buffer.len() / BLOCKSIZE * BLOCKSIZE;
+        let (blocks, partial) = buffer.split_at_mut(full);
+        for (i, block) in blocks.chunks_exact_mut(BLOCKSIZE).enumerate() {
+            xor_in_place(&mut offset, self.l_for(i as u64 + 1));
+            xor_in_place(&mut checksum, block);
+            let block = as_block(block);
+            xor_in_place(block, &offset);
+            self.cipher.encrypt_block(block);
+            xor_in_place(block, &offset);
+        }
+        if !partial.is_empty() {
+            xor_in_place(&mut checksum[..partial.len()], partial);
+            checksum[partial.len()] ^= 0x80;
+            self.crypt_partial(&mut offset, partial);
+        }
+        self.tag(&checksum, &offset, aad)[..self.tag_len].to_vec()
+    }
+
+    /// Decrypts `buffer` in place and checks `tag`. `buffer` is restored to
+    /// the ciphertext if authentication fails. A nonce that is empty or
+    /// longer than 15 bytes fails without touching it.
+    pub fn decrypt_detached(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), Error> {
+        if !(1..BLOCKSIZE).contains(&nonce.len()) {
+            return Err(Error);
+        }
+        let mut offset = self.initial_offset(nonce);
+        let mut checksum = [0; BLOCKSIZE];
+        let full = buffer.len() / BLOCKSIZE * BLOCKSIZE;
+        let (blocks, partial) = buffer.split_at_mut(full);
+        for (i, block) in blocks.chunks_exact_mut(BLOCKSIZE).enumerate() {
+            xor_in_place(&mut offset, self.l_for(i as u64 + 1));
+            let block = as_block(block);
+            xor_in_place(block, &offset);
+            self.cipher.decrypt_block(block);
+            xor_in_place(block, &offset);
+            xor_in_place(&mut checksum, block);
+        }
+        if !partial.is_empty() {
+            self.crypt_partial(&mut offset, partial);
+            xor_in_place(&mut checksum[..partial.len()], partial);
+            checksum[partial.len()] ^= 0x80;
+        }
+        let expected = self.tag(&checksum, &offset, aad);
+        if !ct_eq(&expected[..self.tag_len], tag) {
+            // Encryption is deterministic, so it restores the ciphertext.
+            self.encrypt_detached(nonce, aad, buffer);
+            return Err(Error);
+        }
+        Ok(())
+    }
+
+    fn l_for(&self, index: u64) -> &[u8; BLOCKSIZE] {
+        &self.l[index.trailing_zeros() as usize]
+    }
+
+    /// XORs the pad for the final partial block into `partial` and advances
+    /// `offset` to Offset_*.
+    fn crypt_partial(&self, offset: &mut [u8; BLOCKSIZE], partial: &mut [u8]) {
+        xor_in_place(offset, &self.l_star);
+        let mut pad = *offset;
+        self.cipher.encrypt_block(&mut pad);
+        xor_in_place(partial, &pad);
+    }
+
+    /// Offset_0 for a nonce of 1 to 15 bytes, the callers check the length.
+    fn initial_offset(&self, nonce: &[u8]) -> [u8; BLOCKSIZE] {
+        // num2str(TAGLEN mod 128, 7) || zeros || 1 || N
+        let mut block = [0; BLOCKSIZE];
+        block[0] = (((self.tag_len * 8) % 128) << 1) as u8;
+        block[BLOCKSIZE - nonce.len()..].copy_from_slice(nonce);
+        block[BLOCKSIZE - nonce.len() - 1] |= 1;
+        let bottom = (block[BLOCKSIZE - 1] & 0x3f) as u32;
+        block[BLOCKSIZE - 1] &= 0xc0;
+        self.cipher.encrypt_block(&mut block);
+
+        // Offset_0 are the bits bottom..bottom + 128 of
+        // Stretch = Ktop || (Ktop[1..64] xor Ktop[9..72]).
+        let ktop = u128::from_be_bytes(block);
+        let stretch = ((ktop >> 64) as u64) ^ ((ktop >> 56) as u64);
+        let offset = if bottom == 0 {
+            ktop
+        } else {
+            (ktop << bottom) | (stretch >> (64 - bottom)) as u128
+        };
+        offset.to_be_bytes()
+    }
+
+    fn tag(
+        &self,
+        checksum: &[u8; BLOCKSIZE],
+        offset: &[u8; BLOCKSIZE],
+        aad: &[u8],
+    ) -> [u8; BLOCKSIZE] {
+        let mut tag = *checksum;
+        xor_in_place(&mut tag, offset);
+        xor_in_place(&mut tag, &self.l_dollar);
+        self.cipher.encrypt_block(&mut tag);
+        xor_in_place(&mut tag, &self.hash(aad));
+        tag
+    }
+
+    fn hash(&self, aad: &[u8]) -> [u8; BLOCKSIZE] {
+        let mut offset = [0; BLOCKSIZE];
+        let mut sum = [0; BLOCKSIZE];
+        let mut blocks = aad.chunks_exact(BLOCKSIZE);
+        for (i, block) in blocks.by_ref().enumerate() {
+            xor_in_place(&mut offset, self.l_for(i as u64 + 1));
+            let mut input = offset;
+            xor_in_place(&mut input, block);
+            self.cipher.encrypt_block(&mut input);
+            xor_in_place(&mut sum, &input);
+        }
+        let partial = blocks.remainder();
+        if !partial.is_empty() {
+            xor_in_place(&mut offset, &self.l_star);
+            let mut input = offset;
+            xor_in_place(&mut input[..partial.len()], partial);
+            input[partial.len()] ^= 0x80;
+            self.cipher.encrypt_block(&mut input);
+            xor_in_place(&mut sum, &input);
+        }
+        sum
+    }
+}
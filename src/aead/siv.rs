@@ -0,0 +1,143 @@
+use crate::aead::Error;
+use crate::aes::{Aes128, Aes192, Aes256, Cryptoprovider, BLOCKSIZE};
+use crate::mac::Cmac;
+use crate::modes::Ctr;
+use crate::util::{ct_eq, dbl, xor_in_place};
+
+/// Synthetic initialization vector mode (RFC 5297) on top of any
+/// [`Cryptoprovider`].
+///
+/// Encryption is deterministic: the same associated data and plaintext
+/// always give the same ciphertext, which only reveals repetitions of whole
+/// messages. A nonce, if used, is passed as the last associated data
+/// component.
+pub struct Siv<C: Cryptoprovider> {
+    mac: Cmac<C>,
+    ctr_cipher: C,
+}
+
+impl<C: Cryptoprovider> Siv<C> {
+    /// Most associated data components S2V can take besides the plaintext.
+    pub const MAX_AAD_COMPONENTS: usize = 126;
+
+    /// `mac_cipher` is keyed with the first and `ctr_cipher` with the second
+    /// half of the SIV key.
+    pub fn new(mac_cipher: C, ctr_cipher: C) -> Self {
+        Self {
+            mac: Cmac::new(mac_cipher),
+            ctr_cipher,
+        }
+    }
+
+    /// Encrypts `buffer` in place and prepends the synthetic iv, giving the
+    /// `V || C` output of the RFC.
+    pub fn encrypt(&self, aad: &[&[u8]], buffer: &mut Vec<u8>) {
+        let siv = self.encrypt_detached(aad, buffer);
+        buffer.splice(0..0, siv);
+    }
+
+    /// Verifies and strips the synthetic iv at the front of `buffer` and
+    /// decrypts the rest in place. `buffer` is left untouched if
+    /// authentication fails.
+    pub fn decrypt(&self, aad: &[&[u8]], buffer: &mut Vec<u8>) -> Result<(), Error> {
+        if buffer.len() < BLOCKSIZE {
+            return Err(Error);
+        }
+        let (siv, ciphertext) = buffer.split_at_mut(BLOCKSIZE);
+        let siv: [u8; BLOCKSIZE] = (&*siv).try_into().unwrap();
+        self.decrypt_detached(aad, ciphertext, &siv)?;
+        buffer.drain(..BLOCKSIZE);
+        Ok(())
+    }
+
+    /// Encrypts `buffer` in place and returns the synthetic iv.
+    pub fn encrypt_detached(&self, aad: &[&[u8]], buffer: &mut [u8]) -> [u8; BLOCKSIZE] {
+        let siv = self.s2v(aad, buffer);
+        self.ctr(&siv, buffer);
+        siv
+    }
+
+    /// Decrypts `buffer` in place and checks it against `siv`. `buffer` is
+    /// restored to the ciphertext if authentication fails.
+    pub fn decrypt_detached(
+        &self,
+        aad: &[&[u8]],
+        buffer: &mut [u8],
+        siv: &[u8; BLOCKSIZE],
+    ) -> Result<(), Error> {
+        self.ctr(siv, buffer);
+        let expected = self.s2v(aad, buffer);
+        if !ct_eq(&expected, siv) {
+            self.ctr(siv, buffer);
+            return Err(Error);
+        }
+        Ok(())
+    }
+
+    fn s2v(&self, aad: &[&[u8]], plaintext: &[u8]) -> [u8; BLOCKSIZE] {
+        assert!(
+            aad.len() <= Self::MAX_AAD_COMPONENTS,
+            "SIV takes at most 126 associated data components"
+        );
+        let mut d = self.mac.mac(&[0; BLOCKSIZE]);
+        for component in aad {
+            d = dbl(&d);
+            xor_in_place(&mut d, &self.mac.mac(component));
+        }
+        if plaintext.len() >= BLOCKSIZE {
+            let mut t = plaintext.to_vec();
+            let offset = t.len() - BLOCKSIZE;
+            xor_in_place(&mut t[offset..], &d);
+            self.mac.mac(&t)
+        } else {
+            let mut t = dbl(&d);
+            xor_in_place(&mut t[..plaintext.len()], plaintext);
+            t[plaintext.len()] ^= 0x80;
+            self.mac.mac(&t)
+        }
+    }
+
+    fn ctr(&self, siv: &[u8; BLOCKSIZE], buffer: &mut [u8]) {
+        // Clearing the top bit of each of the two low 32-bit words lets
+        // implementations use 64-bit or 32-bit counters without carries.
+        let mut q = *siv;
+        q[8] &= 0x7f;
+        q[12] &= 0x7f;
+        Ctr::new(&self.ctr_cipher, &q)
+            .apply_keystream(buffer)
+            .expect("a 128 bit counter with cleared bits can not overflow");
+    }
+}
+
+impl Siv<Aes128> {
+    /// AES-SIV with a 256 bit key.
+    pub fn from_key(key: &[u8; 32]) -> Self {
+        let (k1, k2) = key.split_at(16);
+        Self::new(
+            Aes128::new(k1.try_into().unwrap()),
+            Aes128::new(k2.try_into().unwrap()),
+        )
+    }
+}
+
+impl Siv<Aes192> {
+    /// AES-SIV with a 384 bit key.
+    pub fn from_key(key: &[u8; 48]) -> Self {
+        let (k1, k2) = key.split_at(24);
+        Self::new(
+            Aes192::new(k1.try_into().unwrap()),
+            Aes192::new(k2.try_into().unwrap()),
+        )
+    }
+}
+
+impl Siv<Aes256> {
+    /// AES-SIV with a 512 bit key.
+    pub fn from_key(key: &[u8; 64]) -> Self {
+        let (k1, k2) = key.split_at(32);
+        Self::new(
+            Aes256::new(k1.try_into().unwrap()),
+            Aes256::new(k2.try_into().unwrap()),
+        )
+    }
+}
@@ -1,14 +1,22 @@
 use mightrix::{ColumnPrio, ColumnPrioMatrix, Reftrix, RowPrio, RowPrioMatrix, Stacktrix};
 
+use crate::error::Error;
 use crate::macros::impl_cryptoprovider;
+use crate::util::{as_block, ct_eq_mask, ct_le_mask};
 use core::panic;
+use std::hint::black_box;
+
+#[cfg(target_arch = "x86_64")]
+mod aesni;
+mod bitsliced;
+mod ttable;
 
 type Matrix<'a> = Reftrix<'a, 4, 4, ColumnPrio, u8>;
 
 const EXPANDED_KEYSIZE_AES128: usize = 16 * 11;
 const EXPANDED_KEYSIZE_AES192: usize = 16 * 13;
 const EXPANDED_KEYSIZE_AES256: usize = 16 * 15;
-const BLOCKSIZE: usize = 16;
+pub const BLOCKSIZE: usize = 16;
 const WORDSIZE: usize = 4;
 
 const SBOX: [u8; 256] = [
@@ -61,30 +69,250 @@ const INVMIXMATRIX: Stacktrix<16, 4, 4, RowPrio, u8> = Stacktrix::with_values([
 ]);
 
 pub trait Cryptoprovider {
-    fn encrypt(&self, block: &mut Vec<u8>);
-    fn decrypt(&self, block: &mut Vec<u8>);
+    /// Pads `buffer` and encrypts it in place.
+    ///
+    /// Panics where [`Cryptoprovider::try_encrypt`] fails.
+    fn encrypt(&self, buffer: &mut Vec<u8>) {
+        if let Err(e) = self.try_encrypt(buffer) {
+            panic!("encryption failed: {e}");
+        }
+    }
+
+    /// Decrypts `buffer` in place and strips the padding.
+    ///
+    /// Panics where [`Cryptoprovider::try_decrypt`] fails, which includes any
+    /// corrupted or forged ciphertext. Use `try_decrypt` for untrusted input.
+    fn decrypt(&self, buffer: &mut Vec<u8>) {
+        if let Err(e) = self.try_decrypt(buffer) {
+            panic!("decryption failed: {e}");
+        }
+    }
+
+    /// Pads `buffer` and encrypts it in place. Fails without touching
+    /// `buffer` if the padding does not make it block aligned.
+    fn try_encrypt(&self, buffer: &mut Vec<u8>) -> Result<(), Error>;
+
+    /// Decrypts `buffer` in place and strips the padding, every padding byte
+    /// is checked. On failure `buffer` still holds the ciphertext.
+    fn try_decrypt(&self, buffer: &mut Vec<u8>) -> Result<(), Error>;
+
     fn encrypt_block(&self, buffer: &mut [u8; BLOCKSIZE]);
     fn decrypt_block(&self, buffer: &mut [u8; BLOCKSIZE]);
 }
 
+impl<C: Cryptoprovider + ?Sized> Cryptoprovider for &C {
+    fn encrypt(&self, buffer: &mut Vec<u8>) {
+        (**self).encrypt(buffer)
+    }
+
+    fn decrypt(&self, buffer: &mut Vec<u8>) {
+        (**self).decrypt(buffer)
+    }
+
+    fn try_encrypt(&self, buffer: &mut Vec<u8>) -> Result<(), Error> {
+        (**self).try_encrypt(buffer)
+    }
+
+    fn try_decrypt(&self, buffer: &mut Vec<u8>) -> Result<(), Error> {
+        (**self).try_decrypt(buffer)
+    }
+
+    fn encrypt_block(&self, buffer: &mut [u8; BLOCKSIZE]) {
+        (**self).encrypt_block(buffer)
+    }
+
+    fn decrypt_block(&self, buffer: &mut [u8; BLOCKSIZE]) {
+        (**self).decrypt_block(buffer)
+    }
+}
+
 pub struct Aes128 {
     expanded_key: [u8; EXPANDED_KEYSIZE_AES128],
     padding: PaddingStrategy,
+    engine: Engine,
 }
 pub struct Aes192 {
     expanded_key: [u8; EXPANDED_KEYSIZE_AES192],
     padding: PaddingStrategy,
+    engine: Engine,
 }
 pub struct Aes256 {
     expanded_key: [u8; EXPANDED_KEYSIZE_AES256],
     padding: PaddingStrategy,
+    engine: Engine,
 }
 
-#[derive(Default)]
+/// The implementation of the block function an AES instance runs. All of
+/// them produce the same output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Follows FIPS 197 step by step, kept to check the others against.
+    /// The S-box lookups are indexed with secret bytes, which leaks the key
+    /// through cache timing.
+    Reference,
+    /// Four lookups per column and round into tables that combine SubBytes,
+    /// ShiftRows and MixColumns. The fastest software backend, but the
+    /// lookups leak through cache timing like the reference one.
+    TTable,
+    /// The AES-NI instructions of x86_64, fast and constant time. Falls back
+    /// to [`Backend::TTable`] on CPUs without them, [`Aes128::backend`]
+    /// tells which one an instance ended up with.
+    AesNi,
+    /// Bitsliced over four blocks with the S-box as a Boolean circuit, no
+    /// memory access depends on the key or the data. Slower for single
+    /// blocks, [`Cryptoprovider::encrypt`] processes four at a time.
+    Bitsliced,
+}
+
+impl Default for Backend {
+    /// AES-NI where the CPU has it, the T-table backend otherwise.
+    fn default() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        if aesni::is_available() {
+            return Backend::AesNi;
+        }
+        Backend::TTable
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum PaddingStrategy {
+    /// PKCS#7 (RFC 5652): n bytes of value n, always at least one byte.
     #[default]
     PKCS7,
+    /// Zeros up to the block boundary, nothing for block aligned data. Zeros
+    /// at the end of the data are stripped along with the padding.
     ZERO,
+    /// ISO/IEC 7816-4: a single 0x80 followed by zeros, always at least one
+    /// byte.
+    ISO7816,
+    /// ANSI X9.23: zeros followed by the padding length, always at least one
+    /// byte.
+    ANSIX923,
+    /// ISO 10126: random bytes followed by the padding length, always at
+    /// least one byte.
+    ISO10126,
+    /// No padding, the data has to be block aligned already.
+    NONE,
+}
+
+impl PaddingStrategy {
+    /// Pads `buffer`. Fails and leaves it untouched for [`PaddingStrategy::NONE`]
+    /// if `buffer` is not block aligned.
+    pub(crate) fn try_pad(&self, buffer: &mut Vec<u8>) -> Result<(), Error> {
+        // Bytes up to the next block boundary, a full block if aligned.
+        let padding_len = BLOCKSIZE - (buffer.len() % BLOCKSIZE);
+        match self {
+            PaddingStrategy::PKCS7 => {
+                buffer.extend(std::iter::repeat_n(padding_len as u8, padding_len));
+            }
+            PaddingStrategy::ZERO => {
+                buffer.extend(std::iter::repeat_n(0u8, padding_len % BLOCKSIZE));
+            }
+            PaddingStrategy::ISO7816 => {
+                buffer.push(0x80);
+                buffer.extend(std::iter::repeat_n(0u8, padding_len - 1));
+            }
+            PaddingStrategy::ANSIX923 => {
+                buffer.extend(std::iter::repeat_n(0u8, padding_len - 1));
+                buffer.push(padding_len as u8);
+            }
+            PaddingStrategy::ISO10126 => {
+                let len = buffer.len();
+                buffer.resize(len + padding_len - 1, 0);
+                getrandom::fill(&mut buffer[len..]).expect("no operating system randomness");
+                buffer.push(padding_len as u8);
+            }
+            PaddingStrategy::NONE => {
+                if !buffer.len().is_multiple_of(BLOCKSIZE) {
+                    return Err(Error::NotBlockAligned);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Length of `buffer` without its padding, or `None` if the padding is
+    /// malformed.
+    ///
+    /// Only the length of `buffer` is branched on. The final block is always
+    /// inspected in full with masks, so neither the padding nor where it is
+    /// malformed shows in the timing, which would otherwise be a padding
+    /// oracle for CBC.
+    pub(crate) fn unpadded_len(&self, buffer: &[u8]) -> Option<usize> {
+        let (valid, padding_len) = match self {
+            PaddingStrategy::ZERO => {
+                let block = &buffer[buffer.len().saturating_sub(BLOCKSIZE)..];
+                return Some(buffer.len() - black_box(trailing_zeros(block)));
+            }
+            PaddingStrategy::NONE => return Some(buffer.len()),
+            _ if buffer.len() < BLOCKSIZE => return None,
+            PaddingStrategy::PKCS7 => length_padding_mask(last_block(buffer), ct_eq_mask),
+            PaddingStrategy::ANSIX923 => {
+                length_padding_mask(last_block(buffer), |b, _| ct_eq_mask(b, 0))
+            }
+            PaddingStrategy::ISO10126 => length_padding_mask(last_block(buffer), |_, _| 0xff),
+            PaddingStrategy::ISO7816 => iso7816_padding_mask(last_block(buffer)),
+        };
+        (black_box(valid) == 0xff).then(|| buffer.len() - padding_len as usize)
+    }
+
+    pub(crate) fn try_unpad(&self, buffer: &mut Vec<u8>) -> Result<(), Error> {
+        let len = self.unpadded_len(buffer).ok_or(Error::InvalidPadding)?;
+        buffer.truncate(len);
+        Ok(())
+    }
+}
+
+fn last_block(buffer: &[u8]) -> &[u8; BLOCKSIZE] {
+    buffer[buffer.len() - BLOCKSIZE..].try_into().unwrap()
+}
+
+/// Checks padding that ends in its length like PKCS#7, ANSI X9.23 and
+/// ISO 10126. Returns 0xff if the length is in 1..=16 and `filler` accepts
+/// every other padding byte, which it gets along with the length, and 0
+/// otherwise, together with the length.
+///
+/// Never branches on or exits early because of the content of `block`.
+#[inline(never)]
+fn length_padding_mask(block: &[u8; BLOCKSIZE], filler: impl Fn(u8, u8) -> u8) -> (u8, u8) {
+    let padding_len = block[BLOCKSIZE - 1];
+    let mut valid = !ct_eq_mask(padding_len, 0) & ct_le_mask(padding_len, BLOCKSIZE as u8);
+    for (i, b) in block.iter().rev().enumerate().skip(1) {
+        let in_padding = ct_le_mask(i as u8 + 1, padding_len);
+        // Keeps the optimizer from exiting early once `valid` is zero.
+        valid = black_box(valid & (!in_padding | filler(*b, padding_len)));
+    }
+    (valid, padding_len)
+}
+
+/// Checks ISO/IEC 7816-4 padding: 0xff if `block` ends in 0x80 followed by
+/// zeros and 0 otherwise, together with the padding length.
+///
+/// Never branches on or exits early because of the content of `block`.
+#[inline(never)]
+fn iso7816_padding_mask(block: &[u8; BLOCKSIZE]) -> (u8, u8) {
+    let mut trailing = 0xffu8;
+    let mut found = 0u8;
+    let mut padding_len = 0u8;
+    for (i, b) in block.iter().rev().enumerate() {
+        let marker = trailing & ct_eq_mask(*b, 0x80);
+        found |= marker;
+        padding_len |= marker & (i as u8 + 1);
+        trailing = black_box(trailing & ct_eq_mask(*b, 0));
+    }
+    (found, padding_len)
+}
+
+/// Number of zeros at the end of `block`, counted without branching.
+fn trailing_zeros(block: &[u8]) -> usize {
+    let mut trailing = 0xffu8;
+    let mut count = 0;
+    for b in block.iter().rev() {
+        trailing = black_box(trailing & ct_eq_mask(*b, 0));
+        count += (trailing & 1) as usize;
+    }
+    count
 }
 
 impl Aes128 {
@@ -93,15 +321,26 @@ impl Aes128 {
     const NK: usize = Self::KEYSIZE / 4;
 
     pub fn new(key: &[u8; Aes128::KEYSIZE]) -> Self {
-        Self {
-            expanded_key: Self::key_expansion(key),
-            padding: Default::default(),
-        }
+        Self::with_padding_and_backend(key, Default::default(), Default::default())
     }
 
     pub fn with_padding(key: &[u8; Aes128::KEYSIZE], padding: PaddingStrategy) -> Self {
+        Self::with_padding_and_backend(key, padding, Default::default())
+    }
+
+    pub fn with_backend(key: &[u8; Aes128::KEYSIZE], backend: Backend) -> Self {
+        Self::with_padding_and_backend(key, Default::default(), backend)
+    }
+
+    pub fn with_padding_and_backend(
+        key: &[u8; Aes128::KEYSIZE],
+        padding: PaddingStrategy,
+        backend: Backend,
+    ) -> Self {
+        let expanded_key = Self::key_expansion(key);
         Self {
-            expanded_key: Self::key_expansion(key),
+            engine: Engine::new(backend, &expanded_key),
+            expanded_key,
             padding,
         }
     }
@@ -110,7 +349,7 @@ impl Aes128 {
         let mut expanded = [0; EXPANDED_KEYSIZE_AES128];
         expanded[..key.len()].copy_from_slice(key);
         let mut temp = [0; WORDSIZE];
-        for i in Self::NK..WORDSIZE * 11 {
+        for i in Self::NK..WORDSIZE * (Self::ROUNDS + 1) {
             let (a, b) = expanded.split_at_mut(i * WORDSIZE);
             let (a, t) = a.split_at((i - 1) * WORDSIZE);
             temp.copy_from_slice(t);
@@ -135,15 +374,26 @@ impl Aes192 {
     const NK: usize = Self::KEYSIZE / 4;
 
     pub fn new(key: &[u8; Aes192::KEYSIZE]) -> Self {
-        Self {
-            expanded_key: Self::key_expansion(key),
-            padding: Default::default(),
-        }
+        Self::with_padding_and_backend(key, Default::default(), Default::default())
     }
 
     pub fn with_padding(key: &[u8; Aes192::KEYSIZE], padding: PaddingStrategy) -> Self {
+        Self::with_padding_and_backend(key, padding, Default::default())
+    }
+
+    pub fn with_backend(key: &[u8; Aes192::KEYSIZE], backend: Backend) -> Self {
+        Self::with_padding_and_backend(key, Default::default(), backend)
+    }
+
+    pub fn with_padding_and_backend(
+        key: &[u8; Aes192::KEYSIZE],
+        padding: PaddingStrategy,
+        backend: Backend,
+    ) -> Self {
+        let expanded_key = Self::key_expansion(key);
         Self {
-            expanded_key: Self::key_expansion(key),
+            engine: Engine::new(backend, &expanded_key),
+            expanded_key,
             padding,
         }
     }
@@ -152,7 +402,7 @@ impl Aes192 {
         let mut expanded = [0; EXPANDED_KEYSIZE_AES192];
         expanded[..key.len()].copy_from_slice(key);
         let mut temp = [0; WORDSIZE];
-        for i in Self::NK..WORDSIZE * 13 {
+        for i in Self::NK..WORDSIZE * (Self::ROUNDS + 1) {
             let (a, b) = expanded.split_at_mut(i * WORDSIZE);
             let (a, t) = a.split_at((i - 1) * WORDSIZE);
             temp.copy_from_slice(t);
@@ -177,15 +427,26 @@ impl Aes256 {
     const NK: usize = Self::KEYSIZE / 4;
 
     pub fn new(key: &[u8; Aes256::KEYSIZE]) -> Self {
-        Self {
-            expanded_key: Self::key_expansion(key),
-            padding: Default::default(),
-        }
+        Self::with_padding_and_backend(key, Default::default(), Default::default())
     }
 
     pub fn with_padding(key: &[u8; Aes256::KEYSIZE], padding: PaddingStrategy) -> Self {
+        Self::with_padding_and_backend(key, padding, Default::default())
+    }
+
+    pub fn with_backend(key: &[u8; Aes256::KEYSIZE], backend: Backend) -> Self {
+        Self::with_padding_and_backend(key, Default::default(), backend)
+    }
+
+    pub fn with_padding_and_backend(
+        key: &[u8; Aes256::KEYSIZE],
+        padding: PaddingStrategy,
+        backend: Backend,
+    ) -> Self {
+        let expanded_key = Self::key_expansion(key);
         Self {
-            expanded_key: Self::key_expansion(key),
+            engine: Engine::new(backend, &expanded_key),
+            expanded_key,
             padding,
         }
     }
@@ -194,7 +455,7 @@ impl Aes256 {
         let mut expanded = [0; EXPANDED_KEYSIZE_AES256];
         expanded[..key.len()].copy_from_slice(key);
         let mut temp = [0; WORDSIZE];
-        for i in Self::NK..WORDSIZE * 15 {
+        for i in Self::NK..WORDSIZE * (Self::ROUNDS + 1) {
             let (a, b) = expanded.split_at_mut(i * WORDSIZE);
             let (a, t) = a.split_at_mut((i - 1) * WORDSIZE);
             temp.copy_from_slice(t);
@@ -215,10 +476,10 @@ impl Aes256 {
     }
 }
 
+/// The key schedule goes through the S-box circuit for every backend, so
+/// no backend looks up key bytes in a table.
 fn sub_word(word: &mut [u8]) {
-    for el in word.iter_mut() {
-        *el = SBOX[*el as usize];
-    }
+    bitsliced::sub_word(word);
 }
 
 fn rot_word(word: &mut [u8]) {
@@ -232,6 +493,108 @@ fn rot_word(word: &mut [u8]) {
 
 impl_cryptoprovider!(Aes128, Aes192, Aes256);
 
+/// The round keys in the form the selected backend works with.
+enum Engine {
+    Reference,
+    TTable(ttable::RoundKeys),
+    #[cfg(target_arch = "x86_64")]
+    AesNi(aesni::RoundKeys),
+    Bitsliced(bitsliced::RoundKeys),
+}
+
+impl Engine {
+    fn new(backend: Backend, expanded_key: &[u8]) -> Self {
+        match backend {
+            Backend::Reference => Engine::Reference,
+            #[cfg(target_arch = "x86_64")]
+            Backend::AesNi if aesni::is_available() => {
+                Engine::AesNi(aesni::RoundKeys::new(expanded_key))
+            }
+            // Without AES-NI the portable T-table code takes over.
+            Backend::TTable | Backend::AesNi => {
+                Engine::TTable(ttable::RoundKeys::new(expanded_key))
+            }
+            Backend::Bitsliced => Engine::Bitsliced(bitsliced::RoundKeys::new(expanded_key)),
+        }
+    }
+
+    fn backend(&self) -> Backend {
+        match self {
+            Engine::Reference => Backend::Reference,
+            Engine::TTable(_) => Backend::TTable,
+            #[cfg(target_arch = "x86_64")]
+            Engine::AesNi(_) => Backend::AesNi,
+            Engine::Bitsliced(_) => Backend::Bitsliced,
+        }
+    }
+
+    fn encrypt_blocks(&self, expanded_key: &[u8], buffer: &mut [u8]) {
+        match self {
+            Engine::Reference => {
+                for block in buffer.chunks_exact_mut(BLOCKSIZE) {
+                    cipher(expanded_key, as_block(block));
+                }
+            }
+            Engine::TTable(keys) => keys.encrypt_blocks(buffer),
+            #[cfg(target_arch = "x86_64")]
+            Engine::AesNi(keys) => keys.encrypt_blocks(buffer),
+            Engine::Bitsliced(keys) => keys.encrypt_blocks(buffer),
+        }
+    }
+
+    fn decrypt_blocks(&self, expanded_key: &[u8], buffer: &mut [u8]) {
+        match self {
+            Engine::Reference => {
+                for block in buffer.chunks_exact_mut(BLOCKSIZE) {
+                    inv_cipher(expanded_key, as_block(block));
+                }
+            }
+            Engine::TTable(keys) => keys.decrypt_blocks(buffer),
+            #[cfg(target_arch = "x86_64")]
+            Engine::AesNi(keys) => keys.decrypt_blocks(buffer),
+            Engine::Bitsliced(keys) => keys.decrypt_blocks(buffer),
+        }
+    }
+}
+
+/// The block function of FIPS 197, with as many rounds as `expanded_key`
+/// has round keys after the first.
+fn cipher(expanded_key: &[u8], block: &mut [u8; BLOCKSIZE]) {
+    let rounds = expanded_key.len() / BLOCKSIZE - 1;
+    let mut state = Matrix::from_values(&mut block[..]);
+    add_round_key(&mut state, &expanded_key[0..16]);
+    for round in 1..rounds {
+        sub_bytes(&mut state);
+        shift_rows(&mut state);
+        mix_collumns(&mut state);
+        add_round_key(
+            &mut state,
+            &expanded_key[round * BLOCKSIZE..(round + 1) * BLOCKSIZE],
+        );
+    }
+    sub_bytes(&mut state);
+    shift_rows(&mut state);
+    add_round_key(&mut state, &expanded_key[rounds * BLOCKSIZE..]);
+}
+
+fn inv_cipher(expanded_key: &[u8], block: &mut [u8; BLOCKSIZE]) {
+    let rounds = expanded_key.len() / BLOCKSIZE - 1;
+    let mut state = Matrix::from_values(&mut block[..]);
+    add_round_key(&mut state, &expanded_key[rounds * BLOCKSIZE..]);
+    for round in (1..rounds).rev() {
+        inv_shift_rows(&mut state);
+        inv_sub_bytes(&mut state);
+        add_round_key(
+            &mut state,
+            &expanded_key[round * BLOCKSIZE..(round + 1) * BLOCKSIZE],
+        );
+        inv_mix_collumns(&mut state);
+    }
+    inv_shift_rows(&mut state);
+    inv_sub_bytes(&mut state);
+    add_round_key(&mut state, &expanded_key[..BLOCKSIZE]);
+}
+
 #[doc(hidden)]
 fn mix_collumns(state: &mut Matrix) {
     let mut new_col = [0; 4];
@@ -259,7 +622,7 @@ fn mix_collumns(state: &mut Matrix) {
             }
             *e = new_val;
         }
-        for (old, new) in col.iter_mut().zip(new_col.into_iter()) {
+        for (old, new) in col.iter_mut().zip(new_col) {
             *old = new;
         }
     }
@@ -294,7 +657,7 @@ fn inv_mix_collumns(state: &mut Matrix) {
             }
             *e = new_val;
         }
-        for (old, new) in col.iter_mut().zip(new_col.into_iter()) {
+        for (old, new) in col.iter_mut().zip(new_col) {
             *old = new;
         }
     }
@@ -302,25 +665,25 @@ fn inv_mix_collumns(state: &mut Matrix) {
 
 #[inline(always)]
 #[doc(hidden)]
-fn mul_2(val: u8) -> u8 {
+const fn mul_2(val: u8) -> u8 {
     dbl(val)
 }
 
 #[inline(always)]
 #[doc(hidden)]
-fn mul_3(val: u8) -> u8 {
+const fn mul_3(val: u8) -> u8 {
     dbl(val) ^ val
 }
 
 #[inline(always)]
 #[doc(hidden)]
-fn mul_9(val: u8) -> u8 {
+const fn mul_9(val: u8) -> u8 {
     dbl(dbl(dbl(val))) ^ val
 }
 
 #[inline(always)]
 #[doc(hidden)]
-fn mul_11(val: u8) -> u8 {
+const fn mul_11(val: u8) -> u8 {
     let a2 = dbl(val);
     let a4 = dbl(a2);
     let a8 = dbl(a4);
@@ -329,7 +692,7 @@ fn mul_11(val: u8) -> u8 {
 
 #[inline(always)]
 #[doc(hidden)]
-fn mul_13(val: u8) -> u8 {
+const fn mul_13(val: u8) -> u8 {
     let a2 = dbl(val);
     let a4 = dbl(a2);
     let a8 = dbl(a4);
@@ -338,7 +701,7 @@ fn mul_13(val: u8) -> u8 {
 
 #[inline(always)]
 #[doc(hidden)]
-fn mul_14(val: u8) -> u8 {
+const fn mul_14(val: u8) -> u8 {
     let a2 = dbl(val);
     let a4 = dbl(a2);
     let a8 = dbl(a4);
@@ -347,7 +710,7 @@ fn mul_14(val: u8) -> u8 {
 
 #[inline(always)]
 #[doc(hidden)]
-fn dbl(val: u8) -> u8 {
+const fn dbl(val: u8) -> u8 {
     let val = val as u16;
     ((val << 1) ^ (0x200 - ((val & 0x80) >> 7)) & 0x11b) as u8
 }
@@ -464,3 +827,205 @@ fn test_aes_192_key_expansion() {
         ]
     );
 }
+
+/// The straightforward, branching unpadding, as reference for the masked one.
+#[cfg(test)]
+fn reference_unpadded_len(padding: &PaddingStrategy, buffer: &[u8]) -> Option<usize> {
+    let block = &buffer[buffer.len().saturating_sub(BLOCKSIZE)..];
+    let zeros = block.iter().rev().take_while(|b| **b == 0).count();
+    let padding_len = match padding {
+        PaddingStrategy::ZERO => return Some(buffer.len() - zeros),
+        PaddingStrategy::NONE => return Some(buffer.len()),
+        _ if block.len() < BLOCKSIZE => return None,
+        PaddingStrategy::ISO7816 => {
+            if zeros == BLOCKSIZE || block[BLOCKSIZE - 1 - zeros] != 0x80 {
+                return None;
+            }
+            zeros + 1
+        }
+        PaddingStrategy::PKCS7 | PaddingStrategy::ANSIX923 | PaddingStrategy::ISO10126 => {
+            let padding_len = block[BLOCKSIZE - 1] as usize;
+            if !(1..=BLOCKSIZE).contains(&padding_len) {
+                return None;
+            }
+            let filler = &block[BLOCKSIZE - padding_len..BLOCKSIZE - 1];
+            let valid = match padding {
+                PaddingStrategy::PKCS7 => filler.iter().all(|b| *b as usize == padding_len),
+                PaddingStrategy::ANSIX923 => filler.iter().all(|b| *b == 0),
+                _ => true,
+            };
+            if !valid {
+                return None;
+            }
+            padding_len
+        }
+    };
+    Some(buffer.len() - padding_len)
+}
+
+/// Small xorshift generator, good enough to vary test inputs.
+#[cfg(test)]
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn test_unpadded_len_matches_reference() {
+    use PaddingStrategy::*;
+    let mut state = 0x2545f4914f6cdd1d;
+    for padding in [PKCS7, ZERO, ISO7816, ANSIX923, ISO10126, NONE] {
+        for last in 0..=255u8 {
+            for run in 0..=BLOCKSIZE {
+                // A run of `last` at the end of the block, preceded by a
+                // 0x80 marker or zero every now and then and random data.
+                let mut buffer: Vec<u8> = (0..2 * BLOCKSIZE)
+                    .map(|_| xorshift(&mut state) as u8)
+                    .collect();
+                let len = buffer.len();
+                buffer[len - run..].fill(last);
+                match xorshift(&mut state) % 4 {
+                    0 => buffer[len - run - 1] = 0x80,
+                    1 => buffer[len - run - 1] = 0,
+                    _ => {}
+                }
+                for buffer in [&buffer[..], &buffer[BLOCKSIZE..]] {
+                    assert_eq!(
+                        padding.unpadded_len(buffer),
+                        reference_unpadded_len(&padding, buffer),
+                        "{padding:?} {buffer:02x?}"
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            padding.unpadded_len(&[]),
+            reference_unpadded_len(&padding, &[])
+        );
+    }
+    assert_eq!(PKCS7.unpadded_len(&[1]), None);
+    assert_eq!(ZERO.unpadded_len(&[]), Some(0));
+}
+
+/// Welch's t statistic between the timings of `mask` on valid padding and
+/// on padding that `block` corrupts at varying positions.
+#[cfg(test)]
+fn padding_timing_t(
+    block: impl Fn(&mut u64, bool) -> [u8; BLOCKSIZE],
+    mask: impl Fn(&[u8; BLOCKSIZE]) -> (u8, u8),
+) -> f64 {
+    const SAMPLES: usize = 200_000;
+    const CALLS_PER_SAMPLE: usize = 16;
+
+    let mut state = 0x9e3779b97f4a7c15;
+    let mut times = [Vec::with_capacity(SAMPLES), Vec::with_capacity(SAMPLES)];
+    for _ in 0..SAMPLES {
+        let class = (xorshift(&mut state) & 1) as usize;
+        let block = block(&mut state, class == 1);
+
+        let start = std::time::Instant::now();
+        for _ in 0..CALLS_PER_SAMPLE {
+            black_box(mask(black_box(&block)));
+        }
+        times[class].push(start.elapsed().as_nanos() as f64);
+    }
+
+    // Drop the slowest samples, which are dominated by interrupts.
+    let stats = |samples: &mut Vec<f64>| {
+        samples.sort_by(f64::total_cmp);
+        samples.truncate(samples.len() * 9 / 10);
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let var = samples.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var, n)
+    };
+    let (mean_valid, var_valid, n_valid) = stats(&mut times[0]);
+    let (mean_invalid, var_invalid, n_invalid) = stats(&mut times[1]);
+    (mean_valid - mean_invalid) / (var_valid / n_valid + var_invalid / n_invalid).sqrt()
+}
+
+/// A random block ending in `padding_len` bytes of padding built by `fill`
+/// from the padding length and the position within the padding. Corrupted
+/// blocks get one of those padding bytes flipped.
+#[cfg(test)]
+fn padded_block(
+    state: &mut u64,
+    corrupt: bool,
+    fill: impl Fn(usize, usize) -> u8,
+) -> [u8; BLOCKSIZE] {
+    let mut block = [0u8; BLOCKSIZE];
+    block.iter_mut().for_each(|b| *b = xorshift(state) as u8);
+    let padding_len = (xorshift(state) % BLOCKSIZE as u64) as usize + 1;
+    for i in 0..padding_len {
+        block[BLOCKSIZE - padding_len + i] = fill(padding_len, i);
+    }
+    if corrupt {
+        let wrong = BLOCKSIZE - 1 - (xorshift(state) as usize % padding_len);
+        block[wrong] ^= 1 + (xorshift(state) % 255) as u8;
+    }
+    block
+}
+
+/// Timing harness in the spirit of dudect: measures the padding masks of
+/// PKCS#7, ANSI X9.23, ISO 10126 and ISO/IEC 7816-4 on valid padding against
+/// padding that is malformed at varying positions, and compares the two
+/// classes with Welch's t-test. ZERO and NONE have nothing to validate. Only
+/// turning the mask into a result may take different paths, and that path
+/// is the result itself.
+///
+/// Timing is too noisy for every test run, so run it with
+/// `cargo test --release -- --ignored`.
+#[test]
+#[ignore]
+fn test_unpadding_timing_is_independent_of_padding() {
+    let schemes: [(&str, f64); 4] = [
+        (
+            "PKCS7",
+            padding_timing_t(
+                |state, corrupt| padded_block(state, corrupt, |len, _| len as u8),
+                |block| length_padding_mask(block, ct_eq_mask),
+            ),
+        ),
+        (
+            "ANSIX923",
+            padding_timing_t(
+                |state, corrupt| {
+                    padded_block(
+                        state,
+                        corrupt,
+                        |len, i| if i == len - 1 { len as u8 } else { 0 },
+                    )
+                },
+                |block| length_padding_mask(block, |b, _| ct_eq_mask(b, 0)),
+            ),
+        ),
+        (
+            // Only the length byte carries structure, so a corrupted block
+            // gets an out of range length.
+            "ISO10126",
+            padding_timing_t(
+                |state, corrupt| {
+                    let mut block = padded_block(state, false, |len, _| len as u8);
+                    if corrupt {
+                        block[BLOCKSIZE - 1] = (17 + xorshift(state) % 240) as u8;
+                    }
+                    block
+                },
+                |block| length_padding_mask(block, |_, _| 0xff),
+            ),
+        ),
+        (
+            "ISO7816",
+            padding_timing_t(
+                |state, corrupt| padded_block(state, corrupt, |_, i| if i == 0 { 0x80 } else { 0 }),
+                iso7816_padding_mask,
+            ),
+        ),
+    ];
+    // dudect flags |t| > 4.5 as a leak, leave some headroom for noise.
+    for (name, t) in schemes {
+        assert!(t.abs() < 10.0, "{name}: t = {t:.2}");
+    }
+}
@@ -0,0 +1,133 @@
+//! AES with the AES-NI instructions of x86_64. Every function here needs
+//! the `aes` target feature, so an engine is only ever built after
+//! [`is_available`] said yes.
+
+use std::arch::x86_64::{
+    __m128i, _mm_aesdec_si128, _mm_aesdeclast_si128, _mm_aesenc_si128, _mm_aesenclast_si128,
+    _mm_aesimc_si128, _mm_aeskeygenassist_si128, _mm_cvtsi128_si32, _mm_loadu_si128,
+    _mm_set1_epi32, _mm_setr_epi32, _mm_storeu_si128, _mm_xor_si128,
+};
+
+use super::BLOCKSIZE;
+
+/// Blocks in flight at once, `aesenc` has a latency of several cycles but
+/// accepts a new block every cycle.
+const PARALLEL_BLOCKS: usize = 4;
+
+const RCON: [u32; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+pub(super) fn is_available() -> bool {
+    std::arch::is_x86_feature_detected!("aes")
+}
+
+/// The round keys for `aesenc` and, run through `aesimc` for the
+/// equivalent inverse cipher, for `aesdec`.
+pub(super) struct RoundKeys {
+    enc: Vec<__m128i>,
+    dec: Vec<__m128i>,
+}
+
+impl RoundKeys {
+    /// Expands the cipher key found at the start of `expanded_key` again,
+    /// with `aeskeygenassist` doing SubWord.
+    ///
+    /// Panics if the CPU does not support AES-NI.
+    pub(super) fn new(expanded_key: &[u8]) -> Self {
+        assert!(is_available(), "the CPU does not support AES-NI");
+        // SAFETY: AES-NI is available, checked above.
+        unsafe { Self::expand(expanded_key) }
+    }
+
+    #[target_feature(enable = "aes")]
+    unsafe fn expand(expanded_key: &[u8]) -> Self {
+        let rounds = expanded_key.len() / BLOCKSIZE - 1;
+        let nk = rounds - 6;
+        // Words are little-endian, so the first byte of a word is its
+        // lowest one and RotWord is a rotation right by a byte.
+        let mut w: Vec<u32> = expanded_key[..4 * nk]
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        for i in nk..4 * (rounds + 1) {
+            let mut temp = w[i - 1];
+            if i % nk == 0 {
+                temp = sub_word(temp).rotate_right(8) ^ RCON[i / nk - 1];
+            } else if nk > 6 && i % nk == 4 {
+                temp = sub_word(temp);
+            }
+            w.push(w[i - nk] ^ temp);
+        }
+
+        let enc: Vec<__m128i> = w
+            .chunks_exact(4)
+            .map(|key| _mm_setr_epi32(key[0] as i32, key[1] as i32, key[2] as i32, key[3] as i32))
+            .collect();
+        let dec = enc
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, key)| {
+                if i == 0 || i == rounds {
+                    *key
+                } else {
+                    _mm_aesimc_si128(*key)
+                }
+            })
+            .collect();
+        Self { enc, dec }
+    }
+
+    /// Encrypts every block of the block aligned `buffer` in place.
+    pub(super) fn encrypt_blocks(&self, buffer: &mut [u8]) {
+        assert!(buffer.len().is_multiple_of(BLOCKSIZE));
+        // SAFETY: a `RoundKeys` only exists if AES-NI is available.
+        unsafe { run::<false>(&self.enc, buffer) }
+    }
+
+    /// Decrypts every block of the block aligned `buffer` in place.
+    pub(super) fn decrypt_blocks(&self, buffer: &mut [u8]) {
+        assert!(buffer.len().is_multiple_of(BLOCKSIZE));
+        // SAFETY: a `RoundKeys` only exists if AES-NI is available.
+        unsafe { run::<true>(&self.dec, buffer) }
+    }
+}
+
+/// SubWord through `aeskeygenassist`, which substitutes the words in lanes
+/// 1 and 3 and returns the plain substitution of lane 1 in lane 0.
+#[target_feature(enable = "aes")]
+unsafe fn sub_word(word: u32) -> u32 {
+    let assist = _mm_aeskeygenassist_si128::<0>(_mm_set1_epi32(word as i32));
+    _mm_cvtsi128_si32(assist) as u32
+}
+
+/// Runs all rounds over the blocks of `buffer`, interleaving up to
+/// [`PARALLEL_BLOCKS`] of them.
+#[target_feature(enable = "aes")]
+unsafe fn run<const DECRYPT: bool>(keys: &[__m128i], buffer: &mut [u8]) {
+    let rounds = keys.len() - 1;
+    for chunk in buffer.chunks_mut(PARALLEL_BLOCKS * BLOCKSIZE) {
+        let n = chunk.len() / BLOCKSIZE;
+        let mut blocks = [keys[0]; PARALLEL_BLOCKS];
+        for (i, block) in blocks[..n].iter_mut().enumerate() {
+            let data = _mm_loadu_si128(chunk[i * BLOCKSIZE..].as_ptr().cast());
+            *block = _mm_xor_si128(data, *block);
+        }
+        for key in &keys[1..rounds] {
+            for block in blocks[..n].iter_mut() {
+                *block = if DECRYPT {
+                    _mm_aesdec_si128(*block, *key)
+                } else {
+                    _mm_aesenc_si128(*block, *key)
+                };
+            }
+        }
+        for (i, block) in blocks[..n].iter().enumerate() {
+            let out = if DECRYPT {
+                _mm_aesdeclast_si128(*block, keys[rounds])
+            } else {
+                _mm_aesenclast_si128(*block, keys[rounds])
+            };
+            _mm_storeu_si128(chunk[i * BLOCKSIZE..].as_mut_ptr().cast(), out);
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! Bitsliced AES: the state of four blocks is kept as eight `u64` planes,
+//! plane `j` holding bit `j` of every byte. Bit `16 * b + i` of a plane
+//! belongs to byte `i` of block `b`, so within every 16 bit lane the bytes
+//! keep the column-major order of FIPS 197.
+//!
+//! The S-box is evaluated as the Boolean circuit of Boyar and Peralta and
+//! the linear layers are shifts and masks, so nothing is ever indexed by
+//! secret data.
+
+use super::BLOCKSIZE;
+
+/// Number of blocks processed side by side.
+const PARALLEL_BLOCKS: usize = 4;
+const BATCHSIZE: usize = PARALLEL_BLOCKS * BLOCKSIZE;
+
+type State = [u64; 8];
+
+/// Repeats a 16 bit pattern in every lane.
+const fn lanes(pattern: u16) -> u64 {
+    pattern as u64 * 0x0001_0001_0001_0001
+}
+
+/// The round keys, each one sliced and repeated for every block.
+pub(super) struct RoundKeys {
+    keys: Vec<State>,
+}
+
+impl RoundKeys {
+    pub(super) fn new(expanded_key: &[u8]) -> Self {
+        let keys = expanded_key
+            .chunks_exact(BLOCKSIZE)
+            .map(|key| {
+                let mut batch = [0; BATCHSIZE];
+                for block in batch.chunks_exact_mut(BLOCKSIZE) {
+                    block.copy_from_slice(key);
+                }
+                pack(&batch)
+            })
+            .collect();
+        Self { keys }
+    }
+
+    /// Encrypts every block of the block aligned `buffer` in place.
+    pub(super) fn encrypt_blocks(&self, buffer: &mut [u8]) {
+        for_each_batch(buffer, |state| self.encrypt(state));
+    }
+
+    /// Decrypts every block of the block aligned `buffer` in place.
+    pub(super) fn decrypt_blocks(&self, buffer: &mut [u8]) {
+        for_each_batch(buffer, |state| self.decrypt(state));
+    }
+
+    fn encrypt(&self, state: &mut State) {
+        let rounds = self.keys.len() - 1;
+        add_round_key(state, &self.keys[0]);
+        for key in &self.keys[1..rounds] {
+            sub_bytes(state);
+            shift_rows(state);
+            mix_columns(state);
+            add_round_key(state, key);
+        }
+        sub_bytes(state);
+        shift_rows(state);
+        add_round_key(state, &self.keys[rounds]);
+    }
+
+    fn decrypt(&self, state: &mut State) {
+        let rounds = self.keys.len() - 1;
+        add_round_key(state, &self.keys[rounds]);
+        for key in self.keys[1..rounds].iter().rev() {
+            inv_shift_rows(state);
+            inv_sub_bytes(state);
+            add_round_key(state, key);
+            inv_mix_columns(state);
+        }
+        inv_shift_rows(state);
+        inv_sub_bytes(state);
+        add_round_key(state, &self.keys[0]);
+    }
+}
+
+/// Runs `f` over the sliced batches of `buffer`, a short last batch is
+/// filled up with zero blocks.
+fn for_each_batch(buffer: &mut [u8], f: impl Fn(&mut State)) {
+    assert!(buffer.len().is_multiple_of(BLOCKSIZE));
+    for chunk in buffer.chunks_mut(BATCHSIZE) {
+        let mut batch = [0; BATCHSIZE];
+        batch[..chunk.len()].copy_from_slice(chunk);
+        let mut state = pack(&batch);
+        f(&mut state);
+        chunk.copy_from_slice(&unpack(&state)[..chunk.len()]);
+    }
+}
+
+/// Applies the S-box to every byte of a key schedule word.
+pub(super) fn sub_word(word: &mut [u8]) {
+    let mut batch = [0; BATCHSIZE];
+    batch[..word.len()].copy_from_slice(word);
+    let mut state = pack(&batch);
+    sub_bytes(&mut state);
+    word.copy_from_slice(&unpack(&state)[..word.len()]);
+}
+
+fn pack(batch: &[u8; BATCHSIZE]) -> State {
+    let mut state = [0; 8];
+    for (i, byte) in batch.iter().enumerate() {
+        for (j, plane) in state.iter_mut().enumerate() {
+            *plane |= ((*byte as u64 >> j) & 1) << i;
+        }
+    }
+    state
+}
+
+fn unpack(state: &State) -> [u8; BATCHSIZE] {
+    let mut batch = [0; BATCHSIZE];
+    for (i, byte) in batch.iter_mut().enumerate() {
+        for (j, plane) in state.iter().enumerate() {
+            *byte |= (((plane >> i) & 1) as u8) << j;
+        }
+    }
+    batch
+}
+
+fn add_round_key(state: &mut State, key: &State) {
+    for (plane, key) in state.iter_mut().zip(key) {
+        *plane ^= key;
+    }
+}
+
+/// The S-box circuit of Boyar and Peralta, "A depth-16 circuit for the AES
+/// S-box", 113 gates.
+fn sub_bytes(state: &mut State) {
+    let [x7, x6, x5, x4, x3, x2, x1, x0] = *state;
+
+    // Top linear transformation.
+    let y14 = x3 ^ x5;
+    let y13 = x0 ^ x6;
+    let y9 = x0 ^ x3;
+    let y8 = x0 ^ x5;
+    let t0 = x1 ^ x2;
+    let y1 = t0 ^ x7;
+    let y4 = y1 ^ x3;
+    let y12 = y13 ^ y14;
+    let y2 = y1 ^ x0;
+    let y5 = y1 ^ x6;
+    let y3 = y5 ^ y8;
+    let t1 = x4 ^ y12;
+    let y15 = t1 ^ x5;
+    let y20 = t1 ^ x1;
+    let y6 = y15 ^ x7;
+    let y10 = y15 ^ t0;
+    let y11 = y20 ^ y9;
+    let y7 = x7 ^ y11;
+    let y17 = y10 ^ y11;
+    let y19 = y10 ^ y8;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let y18 = x0 ^ y16;
+
+    // Inversion in GF(2^8), shared non-linear part.
+    let t2 = y12 & y15;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t5 = y4 & x7;
+    let t6 = t5 ^ t2;
+    let t7 = y13 & y16;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t12 = y9 & y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let t17 = t4 ^ t14;
+    let t18 = t6 ^ t16;
+    let t19 = t9 ^ t14;
+    let t20 = t11 ^ t16;
+    let t21 = t17 ^ y20;
+    let t22 = t18 ^ y19;
+    let t23 = t19 ^ y21;
+    let t24 = t20 ^ y18;
+
+    let t25 = t21 ^ t22;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let t30 = t23 ^ t24;
+    let t31 = t22 ^ t26;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t34 = t23 ^ t33;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t37 = t36 ^ t34;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+
+    let t41 = t40 ^ t37;
+    let t42 = t29 ^ t33;
+    let t43 = t29 ^ t40;
+    let t44 = t33 ^ t37;
+    let t45 = t42 ^ t41;
+    let z0 = t44 & y15;
+    let z1 = t37 & y6;
+    let z2 = t33 & x7;
+    let z3 = t43 & y16;
+    let z4 = t40 & y1;
+    let z5 = t29 & y7;
+    let z6 = t42 & y11;
+    let z7 = t45 & y17;
+    let z8 = t41 & y10;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z11 = t33 & y4;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z14 = t29 & y2;
+    let z15 = t42 & y9;
+    let z16 = t45 & y14;
+    let z17 = t41 & y8;
+
+    // Bottom linear transformation, including the affine constant.
+    let t46 = z15 ^ z16;
+    let t47 = z10 ^ z11;
+    let t48 = z5 ^ z13;
+    let t49 = z9 ^ z10;
+    let t50 = z2 ^ z12;
+    let t51 = z2 ^ z5;
+    let t52 = z7 ^ z8;
+    let t53 = z0 ^ z3;
+    let t54 = z6 ^ z7;
+    let t55 = z16 ^ z17;
+    let t56 = z12 ^ t48;
+    let t57 = t50 ^ t53;
+    let t58 = z4 ^ t46;
+    let t59 = z3 ^ t54;
+    let t60 = t46 ^ t57;
+    let t61 = z14 ^ t57;
+    let t62 = t52 ^ t58;
+    let t63 = t49 ^ t58;
+    let t64 = z4 ^ t59;
+    let t65 = t61 ^ t62;
+    let t66 = z1 ^ t63;
+    let s0 = t59 ^ t63;
+    let s6 = t56 ^ !t62;
+    let s7 = t48 ^ !t60;
+    let t67 = t64 ^ t65;
+    let s3 = t53 ^ t66;
+    let s4 = t51 ^ t66;
+    let s5 = t47 ^ t65;
+    let s1 = t64 ^ !s3;
+    let s2 = t55 ^ !t67;
+
+    *state = [s7, s6, s5, s4, s3, s2, s1, s0];
+}
+
+/// The S-box is the field inverse followed by an affine map, so its inverse
+/// is the inverse affine map, the S-box, and the inverse affine map again.
+fn inv_sub_bytes(state: &mut State) {
+    inv_affine(state);
+    sub_bytes(state);
+    inv_affine(state);
+}
+
+/// b_i = y_(i+2) ^ y_(i+5) ^ y_(i+7) ^ c_i with c = 0x05.
+fn inv_affine(state: &mut State) {
+    let y = *state;
+    for (i, plane) in state.iter_mut().enumerate() {
+        *plane = y[(i + 2) % 8] ^ y[(i + 5) % 8] ^ y[(i + 7) % 8];
+    }
+    state[0] = !state[0];
+    state[2] = !state[2];
+}
+
+/// Rotates the bytes of row `row` right by `by` bits within every lane.
+fn rotate_row(plane: u64, row: u32, by: u32) -> u64 {
+    let bits = plane & lanes(0x1111 << row);
+    let low = lanes(0xffff >> by);
+    ((bits >> by) & low) | ((bits << (16 - by)) & !low)
+}
+
+fn shift_rows(state: &mut State) {
+    for plane in state.iter_mut() {
+        let x = *plane;
+        *plane =
+            (x & lanes(0x1111)) | rotate_row(x, 1, 4) | rotate_row(x, 2, 8) | rotate_row(x, 3, 12);
+    }
+}
+
+fn inv_shift_rows(state: &mut State) {
+    for plane in state.iter_mut() {
+        let x = *plane;
+        *plane =
+            (x & lanes(0x1111)) | rotate_row(x, 1, 12) | rotate_row(x, 2, 8) | rotate_row(x, 3, 4);
+    }
+}
+
+/// Moves byte `r + by` of every column to row `r`.
+fn rotate_column(plane: u64, by: u32) -> u64 {
+    let low = lanes(0x1111 * (0xf >> by));
+    ((plane >> by) & low) | ((plane << (4 - by)) & !low)
+}
+
+/// Multiplication of every byte by x.
+fn xtime(state: &State) -> State {
+    let [b0, b1, b2, b3, b4, b5, b6, b7] = *state;
+    [b7, b0 ^ b7, b1, b2 ^ b7, b3 ^ b7, b4, b5, b6]
+}
+
+/// 2 * a_r ^ 3 * a_(r+1) ^ a_(r+2) ^ a_(r+3), written as
+/// 2 * (a_r ^ a_(r+1)) ^ a_(r+1) ^ a_(r+2) ^ a_(r+3).
+fn mix_columns(state: &mut State) {
+    let mut sum = [0; 8];
+    let mut rest = [0; 8];
+    for (j, plane) in state.iter().enumerate() {
+        let (r1, r2, r3) = (
+            rotate_column(*plane, 1),
+            rotate_column(*plane, 2),
+            rotate_column(*plane, 3),
+        );
+        sum[j] = plane ^ r1;
+        rest[j] = r1 ^ r2 ^ r3;
+    }
+    for ((plane, doubled), rest) in state.iter_mut().zip(xtime(&sum)).zip(rest) {
+        *plane = doubled ^ rest;
+    }
+}
+
+/// The inverse matrix factors into the forward one times the circulant
+/// (5, 0, 4, 0), i.e. a_r ^= 4 * (a_r ^ a_(r+2)) before mixing.
+fn inv_mix_columns(state: &mut State) {
+    let mut sum = [0; 8];
+    for (j, plane) in state.iter().enumerate() {
+        sum[j] = plane ^ rotate_column(*plane, 2);
+    }
+    for (plane, quadrupled) in state.iter_mut().zip(xtime(&xtime(&sum))) {
+        *plane ^= quadrupled;
+    }
+    mix_columns(state);
+}
+
+#[test]
+fn test_sub_bytes_matches_table() {
+    let mut batch = [0; BATCHSIZE];
+    for chunk in 0..4 {
+        for (i, byte) in batch.iter_mut().enumerate() {
+            *byte = (chunk * BATCHSIZE + i) as u8;
+        }
+        let mut state = pack(&batch);
+        sub_bytes(&mut state);
+        let substituted = unpack(&state);
+        inv_sub_bytes(&mut state);
+        assert_eq!(unpack(&state), batch);
+        for (x, s) in batch.iter().zip(substituted) {
+            assert_eq!(s, super::SBOX[*x as usize], "S({x:#04x})");
+        }
+    }
+}
+
+#[test]
+fn test_pack_round_trip() {
+    let batch: [u8; BATCHSIZE] = core::array::from_fn(|i| (i * 37 + 11) as u8);
+    assert_eq!(unpack(&pack(&batch)), batch);
+}
@@ -0,0 +1,177 @@
+//! Table driven AES on 32 bit words, as in the reference implementation by
+//! Rijmen, Bosselaers and Barreto. The state is four big-endian `u32`
+//! columns and every round but the last is sixteen lookups into tables that
+//! combine SubBytes, ShiftRows and MixColumns.
+//!
+//! Decryption uses the equivalent inverse cipher of FIPS 197, section 5.3.5,
+//! so it runs the same way with the inverse tables and round keys that went
+//! through InvMixColumns.
+
+use super::{mul_11, mul_13, mul_14, mul_2, mul_3, mul_9, BLOCKSIZE, INVSBOX, SBOX};
+use crate::util::as_block;
+
+type Tables = [[u32; 256]; 4];
+
+/// TE[i][x] is the column that the S-box output of `x` in row `i`
+/// contributes after MixColumns.
+static TE: Tables = tables(&SBOX, [2, 1, 1, 3]);
+/// The same for the inverse S-box and InvMixColumns.
+static TD: Tables = tables(&INVSBOX, [14, 9, 13, 11]);
+
+const fn gf_mul(x: u8, factor: u8) -> u8 {
+    match factor {
+        1 => x,
+        2 => mul_2(x),
+        3 => mul_3(x),
+        9 => mul_9(x),
+        11 => mul_11(x),
+        13 => mul_13(x),
+        14 => mul_14(x),
+        _ => panic!("not a MixColumns coefficient"),
+    }
+}
+
+/// Builds the four tables from the first column of the mixing matrix, the
+/// others are that column rotated down by the row.
+const fn tables(sbox: &[u8; 256], column: [u8; 4]) -> Tables {
+    let mut tables = [[0; 256]; 4];
+    let mut x = 0;
+    while x < 256 {
+        let s = sbox[x];
+        let word = u32::from_be_bytes([
+            gf_mul(s, column[0]),
+            gf_mul(s, column[1]),
+            gf_mul(s, column[2]),
+            gf_mul(s, column[3]),
+        ]);
+        let mut row = 0;
+        while row < 4 {
+            tables[row][x] = word.rotate_right(8 * row as u32);
+            row += 1;
+        }
+        x += 1;
+    }
+    tables
+}
+
+/// Byte `row` of a column, counted from the most significant end.
+#[inline(always)]
+fn byte(word: u32, row: usize) -> usize {
+    (word >> (24 - 8 * row)) as u8 as usize
+}
+
+fn words(bytes: &[u8]) -> [u32; 4] {
+    core::array::from_fn(|c| u32::from_be_bytes(bytes[4 * c..4 * c + 4].try_into().unwrap()))
+}
+
+/// The round keys as columns, in order for encryption and, transformed for
+/// the equivalent inverse cipher, in order for decryption.
+pub(super) struct RoundKeys {
+    enc: Vec<[u32; 4]>,
+    dec: Vec<[u32; 4]>,
+}
+
+impl RoundKeys {
+    pub(super) fn new(expanded_key: &[u8]) -> Self {
+        let enc: Vec<_> = expanded_key.chunks_exact(BLOCKSIZE).map(words).collect();
+        let rounds = enc.len() - 1;
+        let dec = enc
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, key)| {
+                if i == 0 || i == rounds {
+                    *key
+                } else {
+                    key.map(inv_mix_column)
+                }
+            })
+            .collect();
+        Self { enc, dec }
+    }
+
+    /// Encrypts every block of the block aligned `buffer` in place.
+    pub(super) fn encrypt_blocks(&self, buffer: &mut [u8]) {
+        for block in buffer.chunks_exact_mut(BLOCKSIZE) {
+            self.encrypt_block(as_block(block));
+        }
+    }
+
+    /// Decrypts every block of the block aligned `buffer` in place.
+    pub(super) fn decrypt_blocks(&self, buffer: &mut [u8]) {
+        for block in buffer.chunks_exact_mut(BLOCKSIZE) {
+            self.decrypt_block(as_block(block));
+        }
+    }
+
+    fn encrypt_block(&self, block: &mut [u8; BLOCKSIZE]) {
+        let keys = &self.enc;
+        let rounds = keys.len() - 1;
+        let mut s = words(block);
+        for (col, key) in s.iter_mut().zip(keys[0]) {
+            *col ^= key;
+        }
+        // ShiftRows takes row i of column c from column c + i.
+        for key in &keys[1..rounds] {
+            s = core::array::from_fn(|c| {
+                TE[0][byte(s[c], 0)]
+                    ^ TE[1][byte(s[(c + 1) % 4], 1)]
+                    ^ TE[2][byte(s[(c + 2) % 4], 2)]
+                    ^ TE[3][byte(s[(c + 3) % 4], 3)]
+                    ^ key[c]
+            });
+        }
+        let key = keys[rounds];
+        for c in 0..4 {
+            let col = u32::from_be_bytes(core::array::from_fn(|row| {
+                SBOX[byte(s[(c + row) % 4], row)]
+            }));
+            block[4 * c..4 * c + 4].copy_from_slice(&(col ^ key[c]).to_be_bytes());
+        }
+    }
+
+    fn decrypt_block(&self, block: &mut [u8; BLOCKSIZE]) {
+        let keys = &self.dec;
+        let rounds = keys.len() - 1;
+        let mut s = words(block);
+        for (col, key) in s.iter_mut().zip(keys[0]) {
+            *col ^= key;
+        }
+        // InvShiftRows takes row i of column c from column c - i.
+        for key in &keys[1..rounds] {
+            s = core::array::from_fn(|c| {
+                TD[0][byte(s[c], 0)]
+                    ^ TD[1][byte(s[(c + 3) % 4], 1)]
+                    ^ TD[2][byte(s[(c + 2) % 4], 2)]
+                    ^ TD[3][byte(s[(c + 1) % 4], 3)]
+                    ^ key[c]
+            });
+        }
+        let key = keys[rounds];
+        for c in 0..4 {
+            let col = u32::from_be_bytes(core::array::from_fn(|row| {
+                INVSBOX[byte(s[(c + 4 - row) % 4], row)]
+            }));
+            block[4 * c..4 * c + 4].copy_from_slice(&(col ^ key[c]).to_be_bytes());
+        }
+    }
+}
+
+fn inv_mix_column(col: u32) -> u32 {
+    let [a0, a1, a2, a3] = col.to_be_bytes();
+    u32::from_be_bytes([
+        mul_14(a0) ^ mul_11(a1) ^ mul_13(a2) ^ mul_9(a3),
+        mul_9(a0) ^ mul_14(a1) ^ mul_11(a2) ^ mul_13(a3),
+        mul_13(a0) ^ mul_9(a1) ^ mul_14(a2) ^ mul_11(a3),
+        mul_11(a0) ^ mul_13(a1) ^ mul_9(a2) ^ mul_14(a3),
+    ])
+}
+
+#[test]
+fn test_tables_match_fips197_example() {
+    // FIPS 197 Appendix B, first round column 0: 19 f4 8d 08 turn into
+    // d4 bf 5d 30 after SubBytes and ShiftRows, which mix to 04 66 81 e5.
+    let mixed = TE[0][0x19] ^ TE[1][0xf4] ^ TE[2][0x8d] ^ TE[3][0x08];
+    assert_eq!(mixed, 0x046681e5);
+    assert_eq!(inv_mix_column(0x046681e5), 0xd4bf5d30);
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use crate::keywrap::UnwrapError;
+use crate::modes::ctr::CounterOverflow;
+use crate::{aead, mac};
+
+/// Errors of the padded encryption and decryption functions, see
+/// [`Cryptoprovider::try_decrypt`](crate::aes::Cryptoprovider::try_decrypt).
+///
+/// The other modules return their own, narrower errors, which all convert
+/// into this one, so `?` works in functions that use several of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The ciphertext, or the plaintext after padding, is not a multiple of
+    /// the block size.
+    NotBlockAligned,
+    /// The decrypted data does not end in valid padding.
+    InvalidPadding,
+    /// An AEAD ciphertext did not authenticate, see [`aead::Error`].
+    AuthenticationFailed,
+    /// A tag did not match its message, see [`mac::Error`].
+    MacVerificationFailed,
+    /// A wrapped key could not be unwrapped, see [`UnwrapError`].
+    Unwrap(UnwrapError),
+    /// The counter of a CTR instance would wrap, see [`CounterOverflow`].
+    CounterOverflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotBlockAligned => f.write_str("data is not a multiple of the block size"),
+            Error::InvalidPadding => f.write_str("invalid padding"),
+            Error::AuthenticationFailed => aead::Error.fmt(f),
+            Error::MacVerificationFailed => mac::Error.fmt(f),
+            Error::Unwrap(e) => e.fmt(f),
+            Error::CounterOverflow => CounterOverflow.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<aead::Error> for Error {
+    fn from(_: aead::Error) -> Self {
+        Error::AuthenticationFailed
+    }
+}
+
+impl From<mac::Error> for Error {
+    fn from(_: mac::Error) -> Self {
+        Error::MacVerificationFailed
+    }
+}
+
+impl From<UnwrapError> for Error {
+    fn from(e: UnwrapError) -> Self {
+        Error::Unwrap(e)
+    }
+}
+
+impl From<CounterOverflow> for Error {
+    fn from(_: CounterOverflow) -> Self {
+        Error::CounterOverflow
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::util::{as_block, ct_eq};
+
+const SEMIBLOCK: usize = BLOCKSIZE / 2;
+// Default initial value of RFC 3394, section 2.2.3.1.
+const IV: [u8; SEMIBLOCK] = [0xa6; SEMIBLOCK];
+// Constant half of the alternative initial value of RFC 5649, section 3.
+const AIV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+/// Returned when a wrapped key cannot be unwrapped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnwrapError {
+    /// The input cannot be the output of the wrapping function.
+    InvalidLength,
+    /// The integrity check value did not match, the input was modified or
+    /// wrapped under a different KEK.
+    IntegrityCheckFailed,
+}
+
+impl fmt::Display for UnwrapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnwrapError::InvalidLength => f.write_str("invalid wrapped key length"),
+            UnwrapError::IntegrityCheckFailed => f.write_str("key unwrap integrity check failed"),
+        }
+    }
+}
+
+impl std::error::Error for UnwrapError {}
+
+/// AES Key Wrap (RFC 3394) and Key Wrap with Padding (RFC 5649), the KW and
+/// KWP algorithms of NIST SP 800-38F, with `cipher` as the key encryption
+/// key.
+pub struct KeyWrap<C: Cryptoprovider> {
+    cipher: C,
+}
+
+impl<C: Cryptoprovider> KeyWrap<C> {
+    pub fn new(cipher: C) -> Self {
+        Self { cipher }
+    }
+
+    /// Wraps `key` with KW. The key has to be a multiple of 8 bytes and at
+    /// least 16 bytes long, the output is 8 bytes longer than the key.
+    pub fn wrap(&self, key: &[u8]) -> Vec<u8> {
+        assert!(
+            key.len() >= 2 * SEMIBLOCK && key.len().is_multiple_of(SEMIBLOCK),
+            "KW keys are a multiple of 8 bytes and at least 16 bytes long"
+        );
+        let mut buffer = [&IV[..], key].concat();
+        self.wrap_in_place(&mut buffer);
+        buffer
+    }
+
+    /// Unwraps a key wrapped with [`KeyWrap::wrap`].
+    pub fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>, UnwrapError> {
+        if wrapped.len() < 3 * SEMIBLOCK || !wrapped.len().is_multiple_of(SEMIBLOCK) {
+            return Err(UnwrapError::InvalidLength);
+        }
+        let mut buffer = wrapped.to_vec();
+        self.unwrap_in_place(&mut buffer);
+        if !ct_eq(&buffer[..SEMIBLOCK], &IV) {
+            return Err(UnwrapError::IntegrityCheckFailed);
+        }
+        Ok(buffer.split_off(SEMIBLOCK))
+    }
+
+    /// Wraps `key` with KWP, which accepts keys of any length between 1 and
+    /// 2^32 - 1 bytes.
+    pub fn wrap_padded(&self, key: &[u8]) -> Vec<u8> {
+        assert!(
+            !key.is_empty() && key.len() <= u32::MAX as usize,
+            "KWP keys are 1 to 2^32 - 1 bytes long"
+        );
+        let mut buffer = Vec::with_capacity(key.len() + 2 * SEMIBLOCK);
+        buffer.extend_from_slice(&AIV_PREFIX);
+        buffer.extend_from_slice(&(key.len() as u32).to_be_bytes());
+        buffer.extend_from_slice(key);
+        buffer.resize(buffer.len().next_multiple_of(SEMIBLOCK), 0);
+        if buffer.len() == BLOCKSIZE {
+            // A single semiblock of key data is encrypted as one block.
+            self.cipher.encrypt_block(as_block(&mut buffer));
+        } else {
+            self.wrap_in_place(&mut buffer);
+        }
+        buffer
+    }
+
+    /// Unwraps a key wrapped with [`KeyWrap::wrap_padded`].
+    pub fn unwrap_padded(&self, wrapped: &[u8]) -> Result<Vec<u8>, UnwrapError> {
+        if wrapped.len() < 2 * SEMIBLOCK || !wrapped.len().is_multiple_of(SEMIBLOCK) {
+            return Err(UnwrapError::InvalidLength);
+        }
+        let mut buffer = wrapped.to_vec();
+        if buffer.len() == BLOCKSIZE {
+            self.cipher.decrypt_block(as_block(&mut buffer));
+        } else {
+            self.unwrap_in_place(&mut buffer);
+        }
+
+        let padded_len = buffer.len() - SEMIBLOCK;
+        let key_len = u32::from_be_bytes(buffer[4..SEMIBLOCK].try_into().unwrap()) as usize;
+        // The length has to leave between 0 and 7 bytes of padding.
+        let length_ok = key_len <= padded_len && key_len + SEMIBLOCK > padded_len;
+        let pad_start = SEMIBLOCK + key_len.min(padded_len);
+        let padding_ok = buffer[pad_start..].iter().fold(0, |acc, b| acc | b) == 0;
+        let prefix_ok = ct_eq(&buffer[..4], &AIV_PREFIX);
+        if !(prefix_ok & length_ok & padding_ok) {
+            return Err(UnwrapError::IntegrityCheckFailed);
+        }
+        buffer.truncate(SEMIBLOCK + key_len);
+        Ok(buffer.split_off(SEMIBLOCK))
+    }
+
+    /// The wrapping function W of SP 800-38F over `buffer`, which holds the
+    /// initial value followed by n >= 2 semiblocks.
+    fn wrap_in_place(&self, buffer: &mut [u8]) {
+        let n = buffer.len() / SEMIBLOCK - 1;
+        let mut block = [0; BLOCKSIZE];
+        block[..SEMIBLOCK].copy_from_slice(&buffer[..SEMIBLOCK]);
+        for j in 0..6 {
+            for i in 1..=n {
+                let r = &mut buffer[i * SEMIBLOCK..(i + 1) * SEMIBLOCK];
+                block[SEMIBLOCK..].copy_from_slice(r);
+                self.cipher.encrypt_block(&mut block);
+                r.copy_from_slice(&block[SEMIBLOCK..]);
+                let t = (n * j + i) as u64;
+                for (a, t) in block[..SEMIBLOCK].iter_mut().zip(t.to_be_bytes()) {
+                    *a ^= t;
+                }
+            }
+        }
+        buffer[..SEMIBLOCK].copy_from_slice(&block[..SEMIBLOCK]);
+    }
+
+    /// The unwrapping function W^-1, leaves the recovered initial value in
+    /// the first semiblock.
+    fn unwrap_in_place(&self, buffer: &mut [u8]) {
+        let n = buffer.len() / SEMIBLOCK - 1;
+        let mut block = [0; BLOCKSIZE];
+        block[..SEMIBLOCK].copy_from_slice(&buffer[..SEMIBLOCK]);
+        for j in (0..6).rev() {
+            for i in (1..=n).rev() {
+                let t = (n * j + i) as u64;
+                for (a, t) in block[..SEMIBLOCK].iter_mut().zip(t.to_be_bytes()) {
+                    *a ^= t;
+                }
+                let r = &mut buffer[i * SEMIBLOCK..(i + 1) * SEMIBLOCK];
+                block[SEMIBLOCK..].copy_from_slice(r);
+                self.cipher.decrypt_block(&mut block);
+                r.copy_from_slice(&block[SEMIBLOCK..]);
+            }
+        }
+        buffer[..SEMIBLOCK].copy_from_slice(&block[..SEMIBLOCK]);
+    }
+}
@@ -1,2 +1,12 @@
+pub mod aead;
 pub mod aes;
+mod error;
+pub mod keywrap;
+pub mod mac;
 mod macros;
+pub mod modes;
+pub mod stream;
+pub mod universal_hash;
+mod util;
+
+pub use error::Error;
@@ -0,0 +1,23 @@
+use std::fmt;
+
+pub mod cmac;
+pub mod gmac;
+pub mod pmac;
+pub mod poly1305;
+
+pub use cmac::Cmac;
+pub use gmac::Gmac;
+pub use pmac::Pmac;
+pub use poly1305::{Poly1305, Poly1305Aes};
+
+/// Returned when a tag does not match the message under the given key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Error;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("mac verification failed")
+    }
+}
+
+impl std::error::Error for Error {}
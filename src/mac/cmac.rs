@@ -0,0 +1,126 @@
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::mac::Error;
+use crate::util::{ct_eq, dbl, xor_in_place};
+
+/// CMAC (NIST SP 800-38B, RFC 4493), also known as OMAC1, on top of any
+/// [`Cryptoprovider`].
+///
+/// Messages are absorbed with [`Cmac::update`] and authenticated with
+/// [`Cmac::finalize`] or [`Cmac::verify`], both of which reset the instance
+/// for the next message.
+pub struct Cmac<C: Cryptoprovider> {
+    cipher: C,
+    tag_len: usize,
+    k1: [u8; BLOCKSIZE],
+    k2: [u8; BLOCKSIZE],
+    state: [u8; BLOCKSIZE],
+    // The last block is held back until `finalize`, since it is treated
+    // differently depending on whether it is complete.
+    buffer: [u8; BLOCKSIZE],
+    buffer_len: usize,
+}
+
+impl<C: Cryptoprovider> Cmac<C> {
+    pub fn new(cipher: C) -> Self {
+        Self::with_tag_len(cipher, BLOCKSIZE)
+    }
+
+    /// Truncates tags to their first `tag_len` bytes, which has to be in
+    /// 1..=16. SP 800-38B advises against tags shorter than 8 bytes.
+    pub fn with_tag_len(cipher: C, tag_len: usize) -> Self {
+        assert!(
+            (1..=BLOCKSIZE).contains(&tag_len),
+            "CMAC tags are 1 to 16 bytes long"
+        );
+        let mut l = [0; BLOCKSIZE];
+        cipher.encrypt_block(&mut l);
+        let k1 = dbl(&l);
+        let k2 = dbl(&k1);
+        Self::with_subkeys(cipher, tag_len, k1, k2)
+    }
+
+    fn with_subkeys(cipher: C, tag_len: usize, k1: [u8; BLOCKSIZE], k2: [u8; BLOCKSIZE]) -> Self {
+        Self {
+            cipher,
+            tag_len,
+            k1,
+            k2,
+            state: [0; BLOCKSIZE],
+            buffer: [0; BLOCKSIZE],
+            buffer_len: 0,
+        }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// A fresh instance under the same key that borrows the cipher, without
+    /// deriving the subkeys again.
+    pub(crate) fn by_ref(&self) -> Cmac<&C> {
+        Cmac::with_subkeys(&self.cipher, self.tag_len, self.k1, self.k2)
+    }
+
+    pub(crate) fn cipher(&self) -> &C {
+        &self.cipher
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            if self.buffer_len == BLOCKSIZE {
+                xor_in_place(&mut self.state, &self.buffer);
+                self.cipher.encrypt_block(&mut self.state);
+                self.buffer_len = 0;
+            }
+            let n = data.len().min(BLOCKSIZE - self.buffer_len);
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&data[..n]);
+            self.buffer_len += n;
+            data = &data[n..];
+        }
+    }
+
+    /// Returns the tag of everything absorbed so far and resets the state.
+    pub fn finalize(&mut self) -> Vec<u8> {
+        self.finalize_block()[..self.tag_len].to_vec()
+    }
+
+    /// Compares `tag` against the tag of everything absorbed so far in
+    /// constant time and resets the state.
+    pub fn verify(&mut self, tag: &[u8]) -> Result<(), Error> {
+        let expected = self.finalize_block();
+        if ct_eq(&expected[..self.tag_len], tag) {
+            Ok(())
+        } else {
+            Err(Error)
+        }
+    }
+
+    /// Discards everything absorbed since the last tag.
+    pub fn reset(&mut self) {
+        self.state = [0; BLOCKSIZE];
+        self.buffer_len = 0;
+    }
+
+    /// The untruncated tag, resets the state like [`Cmac::finalize`].
+    pub(crate) fn finalize_block(&mut self) -> [u8; BLOCKSIZE] {
+        let mut mac = self.state;
+        xor_in_place(&mut mac[..self.buffer_len], &self.buffer[..self.buffer_len]);
+        if self.buffer_len == BLOCKSIZE {
+            xor_in_place(&mut mac, &self.k1);
+        } else {
+            mac[self.buffer_len] ^= 0x80;
+            xor_in_place(&mut mac, &self.k2);
+        }
+        self.cipher.encrypt_block(&mut mac);
+        self.reset();
+        mac
+    }
+
+    /// One-shot untruncated tag of `data`, leaves the running state alone.
+    pub(crate) fn mac(&self, data: &[u8]) -> [u8; BLOCKSIZE] {
+        let mut cmac = self.by_ref();
+        cmac.update(data);
+        cmac.finalize_block()
+    }
+}
@@ -0,0 +1,44 @@
+use crate::aead::Gcm;
+use crate::aes::Cryptoprovider;
+use crate::mac::Error;
+
+/// GMAC (NIST SP 800-38D), GCM authenticating data without encrypting any.
+///
+/// Unlike CMAC and PMAC every tag needs a fresh nonce, which must never be
+/// reused under the same key, also not for GCM.
+pub struct Gmac<C: Cryptoprovider> {
+    gcm: Gcm<C>,
+}
+
+impl<C: Cryptoprovider> Gmac<C> {
+    pub fn new(cipher: C) -> Self {
+        Self {
+            gcm: Gcm::new(cipher),
+        }
+    }
+
+    /// Truncates tags to `tag_len` bytes, which has to be 4, 8 or in 12..=16
+    /// like for [`Gcm::with_tag_len`].
+    pub fn with_tag_len(cipher: C, tag_len: usize) -> Self {
+        Self {
+            gcm: Gcm::with_tag_len(cipher, tag_len),
+        }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.gcm.tag_len()
+    }
+
+    /// Returns the tag of `data` under `nonce`.
+    pub fn mac(&self, nonce: &[u8], data: &[u8]) -> Vec<u8> {
+        self.gcm.encrypt_detached(nonce, data, &mut [])
+    }
+
+    /// Compares `tag` against the tag of `data` under `nonce` in constant
+    /// time.
+    pub fn verify(&self, nonce: &[u8], data: &[u8], tag: &[u8]) -> Result<(), Error> {
+        self.gcm
+            .decrypt_detached(nonce, data, &mut [], tag)
+            .map_err(|_| Error)
+    }
+}
@@ -0,0 +1,214 @@
+use std::thread;
+
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::mac::Error;
+use crate::util::{ct_eq, dbl, xor_in_place};
+
+/// Every block index of a `u64` sized message has at most 63 trailing zeros.
+const L_TABLE_SIZE: usize = 64;
+/// Below this many blocks per thread, spawning costs more than it saves.
+const MIN_BLOCKS_PER_THREAD: usize = 1024;
+
+/// PMAC1 (Black and Rogaway) on top of any [`Cryptoprovider`].
+///
+/// Every block except the last is enciphered independently of the others, so
+/// long messages can be spread across threads with [`Pmac::update_parallel`].
+/// The API otherwise mirrors [`Cmac`](crate::mac::Cmac).
+pub struct Pmac<C: Cryptoprovider> {
+    cipher: C,
+    tag_len: usize,
+    // L(i) = x^i * L, indexed by the number of trailing zeros of the block
+    // index.
+    l: [[u8; BLOCKSIZE]; L_TABLE_SIZE],
+    // L(-1) = x^-1 * L, masks a complete final block.
+    l_inv: [u8; BLOCKSIZE],
+    // Number of blocks absorbed into `sigma` and the offset of the last one.
+    counter: u64,
+    offset: [u8; BLOCKSIZE],
+    sigma: [u8; BLOCKSIZE],
+    // The last block is held back until `finalize` like in CMAC.
+    buffer: [u8; BLOCKSIZE],
+    buffer_len: usize,
+}
+
+impl<C: Cryptoprovider> Pmac<C> {
+    pub fn new(cipher: C) -> Self {
+        Self::with_tag_len(cipher, BLOCKSIZE)
+    }
+
+    /// Truncates tags to their first `tag_len` bytes, which has to be in
+    /// 1..=16.
+    pub fn with_tag_len(cipher: C, tag_len: usize) -> Self {
+        assert!(
+            (1..=BLOCKSIZE).contains(&tag_len),
+            "PMAC tags are 1 to 16 bytes long"
+        );
+        let mut l = [[0; BLOCKSIZE]; L_TABLE_SIZE];
+        cipher.encrypt_block(&mut l[0]);
+        for i in 1..L_TABLE_SIZE {
+            l[i] = dbl(&l[i - 1]);
+        }
+        let l_inv = halve(&l[0]);
+        Self {
+            cipher,
+            tag_len,
+            l,
+            l_inv,
+            counter: 0,
+            offset: [0; BLOCKSIZE],
+            sigma: [0; BLOCKSIZE],
+            buffer: [0; BLOCKSIZE],
+            buffer_len: 0,
+        }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            if self.buffer_len == BLOCKSIZE {
+                let block = self.buffer;
+                self.absorb_block(&block);
+                self.buffer_len = 0;
+            }
+            let n = data.len().min(BLOCKSIZE - self.buffer_len);
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&data[..n]);
+            self.buffer_len += n;
+            data = &data[n..];
+        }
+    }
+
+    /// Same as [`Pmac::update`], but splits the blocks of `data` across up
+    /// to `threads` threads. Short inputs are absorbed on the calling thread.
+    pub fn update_parallel(&mut self, data: &[u8], threads: usize)
+    where
+        C: Sync,
+    {
+        // Top up the held back block first, so the rest starts on a block
+        // boundary.
+        let head = data.len().min(BLOCKSIZE - self.buffer_len);
+        self.update(&data[..head]);
+        let data = &data[head..];
+        if data.is_empty() {
+            return;
+        }
+        let block = self.buffer;
+        self.absorb_block(&block);
+        self.buffer_len = 0;
+
+        // Everything but the last, possibly complete, block can be absorbed
+        // out of order.
+        let bulk_len = (data.len() - 1) / BLOCKSIZE * BLOCKSIZE;
+        let (bulk, tail) = data.split_at(bulk_len);
+        let blocks = bulk.len() / BLOCKSIZE;
+        let threads = threads.min(blocks / MIN_BLOCKS_PER_THREAD).max(1);
+        let chunk_len = blocks.div_ceil(threads) * BLOCKSIZE;
+        let first = self.counter + 1;
+
+        let sums = if threads == 1 {
+            vec![self.sum_blocks(first, bulk)]
+        } else {
+            let this = &*self;
+            thread::scope(|scope| {
+                let handles: Vec<_> = bulk
+                    .chunks(chunk_len)
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        let first = first + (i * chunk_len / BLOCKSIZE) as u64;
+                        scope.spawn(move || this.sum_blocks(first, chunk))
+                    })
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            })
+        };
+        for sum in sums {
+            xor_in_place(&mut self.sigma, &sum);
+        }
+        self.counter += blocks as u64;
+        self.offset = self.offset_for(self.counter);
+        self.update(tail);
+    }
+
+    /// Returns the tag of everything absorbed so far and resets the state.
+    pub fn finalize(&mut self) -> Vec<u8> {
+        let mut tag = self.sigma;
+        xor_in_place(&mut tag[..self.buffer_len], &self.buffer[..self.buffer_len]);
+        if self.buffer_len == BLOCKSIZE {
+            xor_in_place(&mut tag, &self.l_inv);
+        } else {
+            tag[self.buffer_len] ^= 0x80;
+        }
+        self.cipher.encrypt_block(&mut tag);
+        self.reset();
+        tag[..self.tag_len].to_vec()
+    }
+
+    /// Compares `tag` against the tag of everything absorbed so far in
+    /// constant time and resets the state.
+    pub fn verify(&mut self, tag: &[u8]) -> Result<(), Error> {
+        if ct_eq(&self.finalize(), tag) {
+            Ok(())
+        } else {
+            Err(Error)
+        }
+    }
+
+    /// Discards everything absorbed since the last tag.
+    pub fn reset(&mut self) {
+        self.counter = 0;
+        self.offset = [0; BLOCKSIZE];
+        self.sigma = [0; BLOCKSIZE];
+        self.buffer_len = 0;
+    }
+
+    fn absorb_block(&mut self, block: &[u8; BLOCKSIZE]) {
+        self.counter += 1;
+        xor_in_place(
+            &mut self.offset,
+            &self.l[self.counter.trailing_zeros() as usize],
+        );
+        let mut block = *block;
+        xor_in_place(&mut block, &self.offset);
+        self.cipher.encrypt_block(&mut block);
+        xor_in_place(&mut self.sigma, &block);
+    }
+
+    /// The XOR of the enciphered blocks of `data`, whose first block has
+    /// index `first`.
+    fn sum_blocks(&self, first: u64, data: &[u8]) -> [u8; BLOCKSIZE] {
+        let mut offset = self.offset_for(first - 1);
+        let mut sum = [0; BLOCKSIZE];
+        for (index, chunk) in (first..).zip(data.chunks_exact(BLOCKSIZE)) {
+            xor_in_place(&mut offset, &self.l[index.trailing_zeros() as usize]);
+            let mut block = [0; BLOCKSIZE];
+            block.copy_from_slice(chunk);
+            xor_in_place(&mut block, &offset);
+            self.cipher.encrypt_block(&mut block);
+            xor_in_place(&mut sum, &block);
+        }
+        sum
+    }
+
+    /// The offset of block `index` without walking all previous ones: the
+    /// sum of L(i) over the bits i set in the Gray code of `index`.
+    fn offset_for(&self, index: u64) -> [u8; BLOCKSIZE] {
+        let gray = index ^ (index >> 1);
+        let mut offset = [0; BLOCKSIZE];
+        for (i, l) in self.l.iter().enumerate() {
+            if gray >> i & 1 == 1 {
+                xor_in_place(&mut offset, l);
+            }
+        }
+        offset
+    }
+}
+
+/// Division by x in GF(2^128), the inverse of [`dbl`].
+fn halve(block: &[u8; BLOCKSIZE]) -> [u8; BLOCKSIZE] {
+    let val = u128::from_be_bytes(*block);
+    let mask = 0u128.wrapping_sub(val & 1);
+    ((val >> 1) ^ (mask & ((1 << 127) | 0x43))).to_be_bytes()
+}
@@ -0,0 +1,210 @@
+use crate::aes::{Aes128, Cryptoprovider, BLOCKSIZE};
+use crate::mac::Error;
+use crate::util::ct_eq;
+
+const LIMB_MASK: u32 = (1 << 26) - 1;
+
+fn le32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// The Poly1305 one-time authenticator (Bernstein), evaluating the message
+/// as a polynomial modulo 2^130 - 5 at the clamped point `r` and masking the
+/// result with `s`.
+///
+/// A key (r, s) must authenticate a single message only, which is why the
+/// instance is consumed by [`Poly1305::finalize`]. Constructions derive a
+/// fresh `s` per message, see [`Poly1305Aes`].
+#[derive(Clone)]
+pub struct Poly1305 {
+    // r and the accumulator h in five 26 bit limbs.
+    r: [u32; 5],
+    h: [u32; 5],
+    s: [u32; 4],
+    buffer: [u8; BLOCKSIZE],
+    buffer_len: usize,
+}
+
+impl Poly1305 {
+    /// Takes the 32 byte key r || s of RFC 8439. The bits of `r` that
+    /// Poly1305 requires to be zero are cleared.
+    pub fn new(key: &[u8; 32]) -> Self {
+        let r = [
+            le32(key, 0) & 0x3ffffff,
+            (le32(key, 3) >> 2) & 0x3ffff03,
+            (le32(key, 6) >> 4) & 0x3ffc0ff,
+            (le32(key, 9) >> 6) & 0x3f03fff,
+            (le32(key, 12) >> 8) & 0x00fffff,
+        ];
+        let s = [le32(key, 16), le32(key, 20), le32(key, 24), le32(key, 28)];
+        Self {
+            r,
+            h: [0; 5],
+            s,
+            buffer: [0; BLOCKSIZE],
+            buffer_len: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        if self.buffer_len > 0 {
+            let n = data.len().min(BLOCKSIZE - self.buffer_len);
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&data[..n]);
+            self.buffer_len += n;
+            data = &data[n..];
+            if self.buffer_len < BLOCKSIZE {
+                return;
+            }
+            let block = self.buffer;
+            self.absorb_block(&block, 1 << 24);
+            self.buffer_len = 0;
+        }
+        let mut blocks = data.chunks_exact(BLOCKSIZE);
+        for block in &mut blocks {
+            self.absorb_block(block, 1 << 24);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; BLOCKSIZE] {
+        if self.buffer_len > 0 {
+            // A partial block gets its 2^(8 * len) bit as a byte in the
+            // block instead of the 2^128 bit.
+            let mut block = [0; BLOCKSIZE];
+            block[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+            block[self.buffer_len] = 1;
+            self.absorb_block(&block, 0);
+        }
+
+        let mut h = self.h;
+        let mut carry;
+        for i in 1..5 {
+            carry = h[i - 1] >> 26;
+            h[i - 1] &= LIMB_MASK;
+            h[i] += carry;
+        }
+        carry = h[4] >> 26;
+        h[4] &= LIMB_MASK;
+        h[0] += carry * 5;
+        carry = h[0] >> 26;
+        h[0] &= LIMB_MASK;
+        h[1] += carry;
+
+        // g = h + 5 - 2^130 replaces h if it does not underflow, which is
+        // the final reduction into [0, 2^130 - 5).
+        let mut g = [0u32; 5];
+        carry = 5;
+        for i in 0..4 {
+            g[i] = h[i] + carry;
+            carry = g[i] >> 26;
+            g[i] &= LIMB_MASK;
+        }
+        g[4] = (h[4] + carry).wrapping_sub(1 << 26);
+        let use_g = (g[4] >> 31).wrapping_sub(1);
+        for i in 0..5 {
+            h[i] = (h[i] & !use_g) | (g[i] & use_g);
+        }
+
+        let words = [
+            h[0] | (h[1] << 26),
+            (h[1] >> 6) | (h[2] << 20),
+            (h[2] >> 12) | (h[3] << 14),
+            (h[3] >> 18) | (h[4] << 8),
+        ];
+        let mut tag = [0; BLOCKSIZE];
+        let mut f = 0u64;
+        for i in 0..4 {
+            f = words[i] as u64 + self.s[i] as u64 + (f >> 32);
+            tag[4 * i..4 * i + 4].copy_from_slice(&(f as u32).to_le_bytes());
+        }
+        tag
+    }
+
+    /// Compares `tag` against the tag of everything absorbed in constant
+    /// time.
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        if ct_eq(&self.finalize(), tag) {
+            Ok(())
+        } else {
+            Err(Error)
+        }
+    }
+
+    /// h = (h + block + hibit * 2^128) * r mod 2^130 - 5, partially reduced.
+    fn absorb_block(&mut self, block: &[u8], hibit: u32) {
+        let [r0, r1, r2, r3, r4] = self.r.map(u64::from);
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+
+        let h = &mut self.h;
+        h[0] += le32(block, 0) & LIMB_MASK;
+        h[1] += (le32(block, 3) >> 2) & LIMB_MASK;
+        h[2] += (le32(block, 6) >> 4) & LIMB_MASK;
+        h[3] += (le32(block, 9) >> 6) & LIMB_MASK;
+        h[4] += (le32(block, 12) >> 8) | hibit;
+        let [h0, h1, h2, h3, h4] = h.map(u64::from);
+
+        let mut d = [
+            h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1,
+            h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2,
+            h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3,
+            h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4,
+            h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0,
+        ];
+        for i in 1..5 {
+            d[i] += d[i - 1] >> 26;
+        }
+        for i in 0..5 {
+            h[i] = d[i] as u32 & LIMB_MASK;
+        }
+        h[0] += (d[4] >> 26) as u32 * 5;
+        h[1] += h[0] >> 26;
+        h[0] &= LIMB_MASK;
+    }
+}
+
+/// Poly1305-AES, the original composition where `s` is the AES-128
+/// encryption of a 16 byte nonce under a second key `k`.
+///
+/// Every nonce must be used for a single message only.
+pub struct Poly1305Aes {
+    cipher: Aes128,
+    r: [u8; BLOCKSIZE],
+}
+
+impl Poly1305Aes {
+    /// Takes the 32 byte key k || r of the Poly1305-AES paper, the AES key
+    /// first.
+    pub fn new(key: &[u8; 32]) -> Self {
+        let (k, r) = key.split_at(BLOCKSIZE);
+        Self {
+            cipher: Aes128::new(k.try_into().unwrap()),
+            r: r.try_into().unwrap(),
+        }
+    }
+
+    /// A [`Poly1305`] instance keyed for `nonce`, for messages that are not
+    /// available as one slice.
+    pub fn with_nonce(&self, nonce: &[u8; BLOCKSIZE]) -> Poly1305 {
+        let mut s = *nonce;
+        self.cipher.encrypt_block(&mut s);
+        let mut key = [0; 32];
+        key[..BLOCKSIZE].copy_from_slice(&self.r);
+        key[BLOCKSIZE..].copy_from_slice(&s);
+        Poly1305::new(&key)
+    }
+
+    pub fn mac(&self, nonce: &[u8; BLOCKSIZE], message: &[u8]) -> [u8; BLOCKSIZE] {
+        let mut poly = self.with_nonce(nonce);
+        poly.update(message);
+        poly.finalize()
+    }
+
+    pub fn verify(&self, nonce: &[u8; BLOCKSIZE], message: &[u8], tag: &[u8]) -> Result<(), Error> {
+        let mut poly = self.with_nonce(nonce);
+        poly.update(message);
+        poly.verify(tag)
+    }
+}
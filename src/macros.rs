@@ -1,90 +1,36 @@
 macro_rules! impl_cryptoprovider {
     ( $($t:ty),+ ) => {
         $(
+        impl $t {
+            /// The implementation this instance runs.
+            pub fn backend(&self) -> Backend {
+                self.engine.backend()
+            }
+        }
+
         impl Cryptoprovider for $t {
-            fn encrypt(&self, buffer: &mut Vec<u8>) {
-                match self.padding {
-                    PaddingStrategy::PKCS7 => {
-                        let padding_len = BLOCKSIZE - (buffer.len() % BLOCKSIZE);
-                        buffer.extend(std::iter::repeat(padding_len as u8).take(padding_len));
-                    },
-                    PaddingStrategy::ZERO => {
-                        let padding_len = (buffer.len() % BLOCKSIZE);
-                        buffer.extend(std::iter::repeat(0u8).take(padding_len));
-                    }
-                }
-                debug_assert!(buffer.len() % BLOCKSIZE == 0);
-                for block in buffer.chunks_exact_mut(BLOCKSIZE) {
-                    self.encrypt_block(
-                        unsafe { &mut *(block as *mut [u8] as *mut [u8; BLOCKSIZE]) }
-                        as &mut [u8; BLOCKSIZE],
-                    );
-                }
+            fn try_encrypt(&self, buffer: &mut Vec<u8>) -> Result<(), Error> {
+                self.padding.try_pad(buffer)?;
+                self.engine.encrypt_blocks(&self.expanded_key, buffer);
+                Ok(())
             }
 
-            fn decrypt(&self, buffer: &mut Vec<u8>) {
-                assert!(buffer.len() % BLOCKSIZE == 0);
-                for block in buffer.chunks_exact_mut(BLOCKSIZE) {
-                    self.decrypt_block(
-                        unsafe { &mut *(block as *mut [u8] as *mut [u8; BLOCKSIZE]) }
-                        as &mut [u8; BLOCKSIZE],
-                    );
-                }
-                match self.padding {
-                    PaddingStrategy::PKCS7 => {
-                        // empty buffer
-                        let Some(padding_len) = buffer.last() else {
-                            return;
-                        };
-                        buffer.truncate(buffer.len() - *padding_len as usize);
-                    },
-                    PaddingStrategy::ZERO => {
-                        let padding_len = buffer.iter().rev().take_while(|b| **b == 0u8).count();
-                        buffer.extend(std::iter::repeat(0u8).take(padding_len));
-                    }
+            fn try_decrypt(&self, buffer: &mut Vec<u8>) -> Result<(), Error> {
+                if !buffer.len().is_multiple_of(BLOCKSIZE) {
+                    return Err(Error::NotBlockAligned);
                 }
+                self.engine.decrypt_blocks(&self.expanded_key, buffer);
+                self.padding.try_unpad(buffer).inspect_err(|_| {
+                    self.engine.encrypt_blocks(&self.expanded_key, buffer);
+                })
             }
 
             fn encrypt_block(&self, block: &mut [u8; BLOCKSIZE]) {
-                assert!(block.len() == BLOCKSIZE);
-                let mut state = Matrix::from_values(&mut block[..]);
-                add_round_key(&mut state, &self.expanded_key[0..16]);
-                for round in 1..Self::ROUNDS {
-                    sub_bytes(&mut state);
-                    shift_rows(&mut state);
-                    mix_collumns(&mut state);
-                    add_round_key(
-                        &mut state,
-                        &self.expanded_key[round * BLOCKSIZE..(round + 1) * BLOCKSIZE],
-                    );
-                }
-                sub_bytes(&mut state);
-                shift_rows(&mut state);
-                add_round_key(
-                    &mut state,
-                    &self.expanded_key[Self::ROUNDS * BLOCKSIZE..],
-                );
+                self.engine.encrypt_blocks(&self.expanded_key, block);
             }
 
             fn decrypt_block(&self, block: &mut [u8; BLOCKSIZE]) {
-                assert!(block.len() == BLOCKSIZE);
-                let mut state = Matrix::from_values(&mut block[..]);
-                add_round_key(
-                    &mut state,
-                    &self.expanded_key[Self::ROUNDS * BLOCKSIZE..(Self::ROUNDS + 1) * BLOCKSIZE],
-                );
-                for round in (1..Self::ROUNDS).into_iter().rev() {
-                    inv_shift_rows(&mut state);
-                    inv_sub_bytes(&mut state);
-                    add_round_key(
-                        &mut state,
-                        &self.expanded_key[round * BLOCKSIZE..(round + 1) * BLOCKSIZE],
-                    );
-                    inv_mix_collumns(&mut state);
-                }
-                inv_shift_rows(&mut state);
-                inv_sub_bytes(&mut state);
-                add_round_key(&mut state, &self.expanded_key[..BLOCKSIZE]);
+                self.engine.decrypt_blocks(&self.expanded_key, block);
             }
         }
         )+
@@ -0,0 +1,30 @@
+pub mod cbc;
+pub mod cbc_cs;
+pub mod cfb;
+pub mod ctr;
+pub mod ecb;
+pub mod ofb;
+pub mod xts;
+
+pub use cbc::Cbc;
+pub use cbc_cs::{CbcCs, CsVariant};
+pub use cfb::{Cfb, SegmentSize};
+pub use ctr::{CounterSize, Ctr};
+pub use ecb::Ecb;
+pub use ofb::{Ofb, OfbState};
+pub use xts::Xts;
+
+/// A mode that processes whole blocks and needs padding for anything else,
+/// as used by the streaming [`Encryptor`](crate::stream::Encryptor).
+///
+/// State such as a chaining value carries over between calls, so a message
+/// can be processed in several block aligned pieces.
+pub trait BlockMode {
+    /// Encrypts `buffer` in place, its length has to be a multiple of the
+    /// block size.
+    fn encrypt_blocks(&mut self, buffer: &mut [u8]);
+
+    /// Decrypts `buffer` in place, its length has to be a multiple of the
+    /// block size.
+    fn decrypt_blocks(&mut self, buffer: &mut [u8]);
+}
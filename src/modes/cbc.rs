@@ -0,0 +1,117 @@
+use crate::aes::{Cryptoprovider, PaddingStrategy, BLOCKSIZE};
+use crate::error::Error;
+use crate::modes::BlockMode;
+use crate::util::{as_block, xor_in_place};
+
+/// Cipher block chaining on top of any [`Cryptoprovider`].
+///
+/// The chaining value starts out as the iv and is carried over between calls,
+/// so a single `Cbc` continues one message across several calls to
+/// [`Cbc::encrypt_blocks`]. Use a fresh, unpredictable iv for every message.
+pub struct Cbc<C: Cryptoprovider> {
+    cipher: C,
+    iv: [u8; BLOCKSIZE],
+    padding: PaddingStrategy,
+}
+
+impl<C: Cryptoprovider> Cbc<C> {
+    pub fn new(cipher: C, iv: &[u8; BLOCKSIZE]) -> Self {
+        Self {
+            cipher,
+            iv: *iv,
+            padding: Default::default(),
+        }
+    }
+
+    pub fn with_padding(cipher: C, iv: &[u8; BLOCKSIZE], padding: PaddingStrategy) -> Self {
+        Self {
+            cipher,
+            iv: *iv,
+            padding,
+        }
+    }
+
+    /// The current chaining value, i.e. the last ciphertext block processed.
+    pub fn iv(&self) -> &[u8; BLOCKSIZE] {
+        &self.iv
+    }
+
+    /// Pads `buffer` and encrypts it in place.
+    ///
+    /// Panics where [`Cbc::try_encrypt`] fails.
+    pub fn encrypt(&mut self, buffer: &mut Vec<u8>) {
+        if let Err(e) = self.try_encrypt(buffer) {
+            panic!("encryption failed: {e}");
+        }
+    }
+
+    /// Decrypts `buffer` in place and strips the padding.
+    ///
+    /// Panics where [`Cbc::try_decrypt`] fails, which includes any
+    /// corrupted or forged ciphertext. Use `try_decrypt` for untrusted input.
+    pub fn decrypt(&mut self, buffer: &mut Vec<u8>) {
+        if let Err(e) = self.try_decrypt(buffer) {
+            panic!("decryption failed: {e}");
+        }
+    }
+
+    /// Pads `buffer` and encrypts it in place. Fails without touching
+    /// `buffer` or the chaining value if the padding does not make it block
+    /// aligned.
+    pub fn try_encrypt(&mut self, buffer: &mut Vec<u8>) -> Result<(), Error> {
+        self.padding.try_pad(buffer)?;
+        self.encrypt_blocks(buffer);
+        Ok(())
+    }
+
+    /// Decrypts `buffer` in place and strips the padding, every padding byte
+    /// is checked. On failure `buffer` still holds the ciphertext and the
+    /// chaining value is unchanged.
+    pub fn try_decrypt(&mut self, buffer: &mut Vec<u8>) -> Result<(), Error> {
+        if !buffer.len().is_multiple_of(BLOCKSIZE) {
+            return Err(Error::NotBlockAligned);
+        }
+        let iv = self.iv;
+        self.decrypt_blocks(buffer);
+        self.padding.try_unpad(buffer).inspect_err(|_| {
+            // Encrypting the plaintext again from the old chaining value
+            // restores the ciphertext.
+            self.iv = iv;
+            self.encrypt_blocks(buffer);
+            self.iv = iv;
+        })
+    }
+
+    /// Encrypts block aligned data in place without touching the padding.
+    pub fn encrypt_blocks(&mut self, buffer: &mut [u8]) {
+        assert!(buffer.len().is_multiple_of(BLOCKSIZE));
+        for block in buffer.chunks_exact_mut(BLOCKSIZE) {
+            xor_in_place(block, &self.iv);
+            let block = as_block(block);
+            self.cipher.encrypt_block(block);
+            self.iv = *block;
+        }
+    }
+
+    /// Decrypts block aligned data in place without touching the padding.
+    pub fn decrypt_blocks(&mut self, buffer: &mut [u8]) {
+        assert!(buffer.len().is_multiple_of(BLOCKSIZE));
+        for block in buffer.chunks_exact_mut(BLOCKSIZE) {
+            let block = as_block(block);
+            let next_iv = *block;
+            self.cipher.decrypt_block(block);
+            xor_in_place(block, &self.iv);
+            self.iv = next_iv;
+        }
+    }
+}
+
+impl<C: Cryptoprovider> BlockMode for Cbc<C> {
+    fn encrypt_blocks(&mut self, buffer: &mut [u8]) {
+        Cbc::encrypt_blocks(self, buffer)
+    }
+
+    fn decrypt_blocks(&mut self, buffer: &mut [u8]) {
+        Cbc::decrypt_blocks(self, buffer)
+    }
+}
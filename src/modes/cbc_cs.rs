@@ -0,0 +1,155 @@
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::util::{as_block, xor_in_place};
+
+/// Where the last two ciphertext blocks go, from the addendum to NIST
+/// SP 800-38A.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsVariant {
+    /// The partial block stays in front of the last full block, block
+    /// aligned messages come out as plain CBC.
+    CS1,
+    /// Like CS3 for messages with a partial block, plain CBC otherwise.
+    CS2,
+    /// The last two blocks are always swapped, as in Kerberos (RFC 3962).
+    CS3,
+}
+
+/// Cipher block chaining with ciphertext stealing, which keeps the length of
+/// the message instead of padding it.
+///
+/// Messages have to hold at least one block. The last block is filled up with
+/// the end of the one before it, whose ciphertext is truncated to the length
+/// of the last plaintext block in turn. Like [`Cbc`](crate::modes::Cbc) the
+/// chaining value carries over, so after a message it is the last block of
+/// the CBC chain, which is what Kerberos uses as the next iv.
+pub struct CbcCs<C: Cryptoprovider> {
+    cipher: C,
+    iv: [u8; BLOCKSIZE],
+    variant: CsVariant,
+}
+
+impl<C: Cryptoprovider> CbcCs<C> {
+    pub fn new(cipher: C, iv: &[u8; BLOCKSIZE], variant: CsVariant) -> Self {
+        Self {
+            cipher,
+            iv: *iv,
+            variant,
+        }
+    }
+
+    /// The current chaining value.
+    pub fn iv(&self) -> &[u8; BLOCKSIZE] {
+        &self.iv
+    }
+
+    /// Encrypts a whole message in place, it has to hold at least one block.
+    pub fn encrypt(&mut self, buffer: &mut [u8]) {
+        assert!(
+            buffer.len() >= BLOCKSIZE,
+            "ciphertext stealing needs at least one block"
+        );
+        let (head, rest, tail) = split_last_two(buffer);
+        for block in head.chunks_exact_mut(BLOCKSIZE) {
+            self.encrypt_block(as_block(block));
+        }
+        if rest.len() == BLOCKSIZE {
+            self.encrypt_block(as_block(rest));
+            return;
+        }
+
+        let mut second_last = [0; BLOCKSIZE];
+        second_last.copy_from_slice(&rest[..BLOCKSIZE]);
+        self.encrypt_block(&mut second_last);
+        // The last block is zero padded, its ciphertext covers the part of
+        // the one before that is cut off.
+        let mut last = [0; BLOCKSIZE];
+        last[..tail].copy_from_slice(&rest[BLOCKSIZE..]);
+        self.encrypt_block(&mut last);
+
+        if self.swaps(tail) {
+            rest[..BLOCKSIZE].copy_from_slice(&last);
+            rest[BLOCKSIZE..].copy_from_slice(&second_last[..tail]);
+        } else {
+            rest[..tail].copy_from_slice(&second_last[..tail]);
+            rest[tail..].copy_from_slice(&last);
+        }
+    }
+
+    /// Decrypts a whole message in place, it has to hold at least one block.
+    pub fn decrypt(&mut self, buffer: &mut [u8]) {
+        assert!(
+            buffer.len() >= BLOCKSIZE,
+            "ciphertext stealing needs at least one block"
+        );
+        let (head, rest, tail) = split_last_two(buffer);
+        for block in head.chunks_exact_mut(BLOCKSIZE) {
+            self.decrypt_block(as_block(block));
+        }
+        if rest.len() == BLOCKSIZE {
+            self.decrypt_block(as_block(rest));
+            return;
+        }
+
+        let mut second_last = [0; BLOCKSIZE];
+        let mut last = [0; BLOCKSIZE];
+        if self.swaps(tail) {
+            last.copy_from_slice(&rest[..BLOCKSIZE]);
+            second_last[..tail].copy_from_slice(&rest[BLOCKSIZE..]);
+        } else {
+            second_last[..tail].copy_from_slice(&rest[..tail]);
+            last.copy_from_slice(&rest[tail..]);
+        }
+        // Decrypting the last block yields the last plaintext block xored
+        // with the full ciphertext of the one before, so the stolen bytes
+        // can be read off it.
+        let mut decrypted = last;
+        self.cipher.decrypt_block(&mut decrypted);
+        second_last[tail..].copy_from_slice(&decrypted[tail..]);
+        xor_in_place(&mut decrypted, &second_last);
+
+        self.decrypt_block(&mut second_last);
+        self.iv = last;
+        rest[..BLOCKSIZE].copy_from_slice(&second_last);
+        rest[BLOCKSIZE..].copy_from_slice(&decrypted[..tail]);
+    }
+
+    /// Whether the last two blocks trade places, given the length of the
+    /// last one.
+    fn swaps(&self, tail: usize) -> bool {
+        match self.variant {
+            CsVariant::CS1 => false,
+            CsVariant::CS2 => tail < BLOCKSIZE,
+            CsVariant::CS3 => true,
+        }
+    }
+
+    fn encrypt_block(&mut self, block: &mut [u8; BLOCKSIZE]) {
+        xor_in_place(block, &self.iv);
+        self.cipher.encrypt_block(block);
+        self.iv = *block;
+    }
+
+    fn decrypt_block(&mut self, block: &mut [u8; BLOCKSIZE]) {
+        let next_iv = *block;
+        self.cipher.decrypt_block(block);
+        xor_in_place(block, &self.iv);
+        self.iv = next_iv;
+    }
+}
+
+/// Splits off the last two blocks, the last of which may be partial, and
+/// returns the length of the last one. A single block message comes back as
+/// one block in the middle.
+fn split_last_two(buffer: &mut [u8]) -> (&mut [u8], &mut [u8], usize) {
+    let tail = match buffer.len() % BLOCKSIZE {
+        0 => BLOCKSIZE,
+        tail => tail,
+    };
+    let rest = if buffer.len() == BLOCKSIZE {
+        BLOCKSIZE
+    } else {
+        BLOCKSIZE + tail
+    };
+    let (head, rest) = buffer.split_at_mut(buffer.len() - rest);
+    (head, rest, tail)
+}
@@ -0,0 +1,97 @@
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+
+/// The number of bits fed back into the shift register per step.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentSize {
+    Cfb1,
+    Cfb8,
+    #[default]
+    Cfb128,
+}
+
+/// Cipher feedback mode on top of any [`Cryptoprovider`].
+///
+/// Works on buffers of any length without padding. The shift register is
+/// carried over between calls, so a message can be split at arbitrary byte
+/// boundaries, even in the middle of a 128 bit segment.
+pub struct Cfb<C: Cryptoprovider> {
+    cipher: C,
+    register: [u8; BLOCKSIZE],
+    segment_size: SegmentSize,
+    // Cfb128 only: keystream of the current segment and the offset into it.
+    keystream: [u8; BLOCKSIZE],
+    pos: usize,
+}
+
+impl<C: Cryptoprovider> Cfb<C> {
+    pub fn new(cipher: C, iv: &[u8; BLOCKSIZE]) -> Self {
+        Self::with_segment_size(cipher, iv, Default::default())
+    }
+
+    pub fn with_segment_size(cipher: C, iv: &[u8; BLOCKSIZE], segment_size: SegmentSize) -> Self {
+        Self {
+            cipher,
+            register: *iv,
+            segment_size,
+            keystream: [0; BLOCKSIZE],
+            pos: 0,
+        }
+    }
+
+    pub fn encrypt(&mut self, buffer: &mut [u8]) {
+        self.process(buffer, false);
+    }
+
+    pub fn decrypt(&mut self, buffer: &mut [u8]) {
+        self.process(buffer, true);
+    }
+
+    fn process(&mut self, buffer: &mut [u8], decrypt: bool) {
+        match self.segment_size {
+            SegmentSize::Cfb1 => {
+                for byte in buffer.iter_mut() {
+                    let mut out = 0;
+                    for bit in (0..8).rev() {
+                        let ks = self.keystream_block()[0] >> 7;
+                        let input = (*byte >> bit) & 1;
+                        let output = input ^ ks;
+                        let feedback = if decrypt { input } else { output };
+                        let register = u128::from_be_bytes(self.register);
+                        self.register = ((register << 1) | feedback as u128).to_be_bytes();
+                        out |= output << bit;
+                    }
+                    *byte = out;
+                }
+            }
+            SegmentSize::Cfb8 => {
+                for byte in buffer.iter_mut() {
+                    let ks = self.keystream_block()[0];
+                    let input = *byte;
+                    *byte ^= ks;
+                    let feedback = if decrypt { input } else { *byte };
+                    self.register.copy_within(1.., 0);
+                    self.register[BLOCKSIZE - 1] = feedback;
+                }
+            }
+            SegmentSize::Cfb128 => {
+                for byte in buffer.iter_mut() {
+                    if self.pos == 0 {
+                        self.keystream = self.keystream_block();
+                    }
+                    let input = *byte;
+                    *byte ^= self.keystream[self.pos];
+                    // The register is only read again at the start of the
+                    // next segment, so the ciphertext can replace it in place.
+                    self.register[self.pos] = if decrypt { input } else { *byte };
+                    self.pos = (self.pos + 1) % BLOCKSIZE;
+                }
+            }
+        }
+    }
+
+    fn keystream_block(&self) -> [u8; BLOCKSIZE] {
+        let mut block = self.register;
+        self.cipher.encrypt_block(&mut block);
+        block
+    }
+}
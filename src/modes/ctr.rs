@@ -0,0 +1,154 @@
+use std::fmt;
+
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::util::xor_in_place;
+
+/// How many trailing bytes of the initial counter block are incremented as a
+/// big-endian counter. The remaining leading bytes are a fixed nonce.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CounterSize {
+    U32,
+    U64,
+    #[default]
+    U128,
+}
+
+impl CounterSize {
+    fn bits(&self) -> u32 {
+        match self {
+            CounterSize::U32 => 32,
+            CounterSize::U64 => 64,
+            CounterSize::U128 => 128,
+        }
+    }
+}
+
+/// Returned when the requested keystream would wrap the counter and thereby
+/// reuse keystream blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CounterOverflow;
+
+impl fmt::Display for CounterOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ctr counter overflow")
+    }
+}
+
+impl std::error::Error for CounterOverflow {}
+
+/// Counter mode on top of any [`Cryptoprovider`].
+///
+/// Turns the block cipher into a stream cipher, so buffers of any length are
+/// processed without padding. Encryption and decryption are the same
+/// operation, see [`Ctr::apply_keystream`].
+pub struct Ctr<C: Cryptoprovider> {
+    cipher: C,
+    initial_block: [u8; BLOCKSIZE],
+    counter_size: CounterSize,
+    // Number of keystream bytes available before the counter wraps.
+    limit: u64,
+    position: u64,
+    keystream: [u8; BLOCKSIZE],
+    keystream_valid: bool,
+}
+
+impl<C: Cryptoprovider> Ctr<C> {
+    /// Uses the whole `initial_block` as a 128-bit counter.
+    pub fn new(cipher: C, initial_block: &[u8; BLOCKSIZE]) -> Self {
+        Self::with_counter_size(cipher, initial_block, Default::default())
+    }
+
+    pub fn with_counter_size(
+        cipher: C,
+        initial_block: &[u8; BLOCKSIZE],
+        counter_size: CounterSize,
+    ) -> Self {
+        let initial = u128::from_be_bytes(*initial_block);
+        let bits = counter_size.bits();
+        let counter = if bits == 128 {
+            initial
+        } else {
+            initial & ((1u128 << bits) - 1)
+        };
+        // 2^bits - counter blocks are left, bits == 128 and counter == 0 is
+        // the only case that does not fit into a u128.
+        let remaining_blocks = match bits {
+            128 => (!counter).checked_add(1),
+            _ => Some((1u128 << bits) - counter),
+        };
+        let limit = remaining_blocks
+            .and_then(|b| b.checked_mul(BLOCKSIZE as u128))
+            .map_or(u64::MAX, |l| l.min(u64::MAX as u128) as u64);
+        Self {
+            cipher,
+            initial_block: *initial_block,
+            counter_size,
+            limit,
+            position: 0,
+            keystream: [0; BLOCKSIZE],
+            keystream_valid: false,
+        }
+    }
+
+    /// The current offset into the keystream in bytes.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Moves to an arbitrary byte offset of the keystream.
+    pub fn seek(&mut self, offset: u64) -> Result<(), CounterOverflow> {
+        if offset > self.limit {
+            return Err(CounterOverflow);
+        }
+        self.position = offset;
+        self.keystream_valid = false;
+        Ok(())
+    }
+
+    /// XORs the keystream into `buffer`, encrypting or decrypting it.
+    ///
+    /// Fails without touching `buffer` if the counter would wrap around.
+    pub fn apply_keystream(&mut self, buffer: &mut [u8]) -> Result<(), CounterOverflow> {
+        let end = self
+            .position
+            .checked_add(buffer.len() as u64)
+            .ok_or(CounterOverflow)?;
+        if end > self.limit {
+            return Err(CounterOverflow);
+        }
+        let mut buffer = buffer;
+        while !buffer.is_empty() {
+            let offset = (self.position % BLOCKSIZE as u64) as usize;
+            if offset == 0 || !self.keystream_valid {
+                self.keystream = self.counter_block(self.position / BLOCKSIZE as u64);
+                self.cipher.encrypt_block(&mut self.keystream);
+                self.keystream_valid = true;
+            }
+            let n = buffer.len().min(BLOCKSIZE - offset);
+            let (head, rest) = buffer.split_at_mut(n);
+            xor_in_place(head, &self.keystream[offset..]);
+            self.position += n as u64;
+            buffer = rest;
+        }
+        Ok(())
+    }
+
+    fn counter_block(&self, index: u64) -> [u8; BLOCKSIZE] {
+        let mut block = self.initial_block;
+        match self.counter_size {
+            CounterSize::U32 => {
+                let ctr = u32::from_be_bytes(block[12..].try_into().unwrap());
+                block[12..].copy_from_slice(&ctr.wrapping_add(index as u32).to_be_bytes());
+            }
+            CounterSize::U64 => {
+                let ctr = u64::from_be_bytes(block[8..].try_into().unwrap());
+                block[8..].copy_from_slice(&ctr.wrapping_add(index).to_be_bytes());
+            }
+            CounterSize::U128 => {
+                let ctr = u128::from_be_bytes(block);
+                block = ctr.wrapping_add(index as u128).to_be_bytes();
+            }
+        }
+        block
+    }
+}
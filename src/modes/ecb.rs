@@ -0,0 +1,34 @@
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::modes::BlockMode;
+use crate::util::as_block;
+
+/// Electronic codebook, every block encrypted on its own.
+///
+/// Equal plaintext blocks give equal ciphertext blocks, so this is only
+/// meant for interoperating with data that already uses it, like the
+/// [`Cryptoprovider::encrypt`] of the AES types.
+pub struct Ecb<C: Cryptoprovider> {
+    cipher: C,
+}
+
+impl<C: Cryptoprovider> Ecb<C> {
+    pub fn new(cipher: C) -> Self {
+        Self { cipher }
+    }
+}
+
+impl<C: Cryptoprovider> BlockMode for Ecb<C> {
+    fn encrypt_blocks(&mut self, buffer: &mut [u8]) {
+        assert!(buffer.len().is_multiple_of(BLOCKSIZE));
+        for block in buffer.chunks_exact_mut(BLOCKSIZE) {
+            self.cipher.encrypt_block(as_block(block));
+        }
+    }
+
+    fn decrypt_blocks(&mut self, buffer: &mut [u8]) {
+        assert!(buffer.len().is_multiple_of(BLOCKSIZE));
+        for block in buffer.chunks_exact_mut(BLOCKSIZE) {
+            self.cipher.decrypt_block(as_block(block));
+        }
+    }
+}
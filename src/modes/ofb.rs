@@ -0,0 +1,83 @@
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::util::xor_in_place;
+
+/// A snapshot of the keystream position of an [`Ofb`].
+///
+/// Together with the key it is all that is needed to continue a transfer,
+/// [`OfbState::to_bytes`] gives a fixed size encoding for persisting it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OfbState {
+    register: [u8; BLOCKSIZE],
+    // Bytes of `register` already used as keystream, BLOCKSIZE means the
+    // next block has to be generated first.
+    pos: usize,
+}
+
+impl OfbState {
+    pub const ENCODED_LEN: usize = BLOCKSIZE + 1;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0; Self::ENCODED_LEN];
+        bytes[..BLOCKSIZE].copy_from_slice(&self.register);
+        bytes[BLOCKSIZE] = self.pos as u8;
+        bytes
+    }
+
+    /// Returns `None` if the encoded byte position is out of range.
+    pub fn from_bytes(bytes: &[u8; Self::ENCODED_LEN]) -> Option<Self> {
+        let pos = bytes[BLOCKSIZE] as usize;
+        if pos > BLOCKSIZE {
+            return None;
+        }
+        Some(Self {
+            register: bytes[..BLOCKSIZE].try_into().unwrap(),
+            pos,
+        })
+    }
+}
+
+/// Output feedback mode on top of any [`Cryptoprovider`].
+///
+/// Turns the block cipher into a stream cipher, so encryption and decryption
+/// are the same operation, see [`Ofb::apply_keystream`].
+pub struct Ofb<C: Cryptoprovider> {
+    cipher: C,
+    state: OfbState,
+}
+
+impl<C: Cryptoprovider> Ofb<C> {
+    pub fn new(cipher: C, iv: &[u8; BLOCKSIZE]) -> Self {
+        Self::from_state(
+            cipher,
+            OfbState {
+                register: *iv,
+                pos: BLOCKSIZE,
+            },
+        )
+    }
+
+    /// Resumes the keystream where [`Ofb::state`] was taken.
+    pub fn from_state(cipher: C, state: OfbState) -> Self {
+        Self { cipher, state }
+    }
+
+    pub fn state(&self) -> OfbState {
+        self.state
+    }
+
+    /// XORs the keystream into `buffer`, encrypting or decrypting it.
+    pub fn apply_keystream(&mut self, buffer: &mut [u8]) {
+        let mut buffer = buffer;
+        while !buffer.is_empty() {
+            if self.state.pos == BLOCKSIZE {
+                self.cipher.encrypt_block(&mut self.state.register);
+                self.state.pos = 0;
+            }
+            let n = buffer.len().min(BLOCKSIZE - self.state.pos);
+            let (head, rest) = buffer.split_at_mut(n);
+            xor_in_place(head, &self.state.register[self.state.pos..]);
+            self.state.pos += n;
+            buffer = rest;
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::aes::{Cryptoprovider, BLOCKSIZE};
+use crate::util::{as_block, ct_eq, xor_in_place};
+
+/// XTS (IEEE 1619) for encrypting fixed size storage sectors.
+///
+/// Uses one cipher for the data and an independently keyed one for the tweak,
+/// e.g. two [`Aes128`](crate::aes::Aes128) for XTS-AES-128 or two
+/// [`Aes256`](crate::aes::Aes256) for XTS-AES-256. Sectors keep their length,
+/// tails that are not a multiple of the block size use ciphertext stealing.
+pub struct Xts<C: Cryptoprovider> {
+    data_cipher: C,
+    tweak_cipher: C,
+}
+
+impl<C: Cryptoprovider> Xts<C> {
+    /// `data_cipher` is keyed with Key1 and `tweak_cipher` with Key2 of the
+    /// standard.
+    ///
+    /// Panics if both use the same key, which IEEE 1619 forbids. Identical
+    /// keys are recognized by encrypting the same block to the same result.
+    pub fn new(data_cipher: C, tweak_cipher: C) -> Self {
+        let mut data_block = [0; BLOCKSIZE];
+        let mut tweak_block = [0; BLOCKSIZE];
+        data_cipher.encrypt_block(&mut data_block);
+        tweak_cipher.encrypt_block(&mut tweak_block);
+        assert!(
+            !ct_eq(&data_block, &tweak_block),
+            "XTS needs two different keys"
+        );
+        Self {
+            data_cipher,
+            tweak_cipher,
+        }
+    }
+
+    /// Encrypts a single sector in place, it has to hold at least one block.
+    pub fn encrypt_sector(&self, sector: u128, buffer: &mut [u8]) {
+        assert!(buffer.len() >= BLOCKSIZE, "XTS needs at least one block");
+        let mut tweak = self.initial_tweak(sector);
+        let tail = buffer.len() % BLOCKSIZE;
+        let full = buffer.len() - tail - if tail == 0 { 0 } else { BLOCKSIZE };
+        let (head, rest) = buffer.split_at_mut(full);
+        for block in head.chunks_exact_mut(BLOCKSIZE) {
+            self.encrypt_block(as_block(block), &tweak);
+            mul_alpha(&mut tweak);
+        }
+        if tail == 0 {
+            return;
+        }
+        // Ciphertext stealing: the last full block borrows the end of its
+        // ciphertext to fill up the partial block, and the swapped pair is
+        // encrypted again under the next tweak.
+        let (last, partial) = rest.split_at_mut(BLOCKSIZE);
+        let last = as_block(last);
+        self.encrypt_block(last, &tweak);
+        mul_alpha(&mut tweak);
+        let mut stolen = *last;
+        stolen[..tail].copy_from_slice(partial);
+        partial.copy_from_slice(&last[..tail]);
+        self.encrypt_block(&mut stolen, &tweak);
+        *last = stolen;
+    }
+
+    /// Decrypts a single sector in place, it has to hold at least one block.
+    pub fn decrypt_sector(&self, sector: u128, buffer: &mut [u8]) {
+        assert!(buffer.len() >= BLOCKSIZE, "XTS needs at least one block");
+        let mut tweak = self.initial_tweak(sector);
+        let tail = buffer.len() % BLOCKSIZE;
+        let full = buffer.len() - tail - if tail == 0 { 0 } else { BLOCKSIZE };
+        let (head, rest) = buffer.split_at_mut(full);
+        for block in head.chunks_exact_mut(BLOCKSIZE) {
+            self.decrypt_block(as_block(block), &tweak);
+            mul_alpha(&mut tweak);
+        }
+        if tail == 0 {
+            return;
+        }
+        let (last, partial) = rest.split_at_mut(BLOCKSIZE);
+        let last = as_block(last);
+        let mut next_tweak = tweak;
+        mul_alpha(&mut next_tweak);
+        self.decrypt_block(last, &next_tweak);
+        let mut stolen = *last;
+        stolen[..tail].copy_from_slice(partial);
+        partial.copy_from_slice(&last[..tail]);
+        self.decrypt_block(&mut stolen, &tweak);
+        *last = stolen;
+    }
+
+    /// Encrypts consecutive sectors of `sector_size` bytes, starting at
+    /// `first_sector`. `sector_size` has to be at least one block and
+    /// `buffer` a whole number of sectors.
+    pub fn encrypt_sectors(&self, first_sector: u128, sector_size: usize, buffer: &mut [u8]) {
+        assert!(
+            sector_size >= BLOCKSIZE,
+            "XTS sectors have to hold at least one block"
+        );
+        assert!(buffer.len().is_multiple_of(sector_size));
+        for (sector, chunk) in (first_sector..).zip(buffer.chunks_exact_mut(sector_size)) {
+            self.encrypt_sector(sector, chunk);
+        }
+    }
+
+    /// Decrypts consecutive sectors of `sector_size` bytes, starting at
+    /// `first_sector`. `sector_size` has to be at least one block and
+    /// `buffer` a whole number of sectors.
+    pub fn decrypt_sectors(&self, first_sector: u128, sector_size: usize, buffer: &mut [u8]) {
+        assert!(
+            sector_size >= BLOCKSIZE,
+            "XTS sectors have to hold at least one block"
+        );
+        assert!(buffer.len().is_multiple_of(sector_size));
+        for (sector, chunk) in (first_sector..).zip(buffer.chunks_exact_mut(sector_size)) {
+            self.decrypt_sector(sector, chunk);
+        }
+    }
+
+    fn initial_tweak(&self, sector: u128) -> [u8; BLOCKSIZE] {
+        let mut tweak = sector.to_le_bytes();
+        self.tweak_cipher.encrypt_block(&mut tweak);
+        tweak
+    }
+
+    fn encrypt_block(&self, block: &mut [u8; BLOCKSIZE], tweak: &[u8; BLOCKSIZE]) {
+        xor_in_place(block, tweak);
+        self.data_cipher.encrypt_block(block);
+        xor_in_place(block, tweak);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8; BLOCKSIZE], tweak: &[u8; BLOCKSIZE]) {
+        xor_in_place(block, tweak);
+        self.data_cipher.decrypt_block(block);
+        xor_in_place(block, tweak);
+    }
+}
+
+/// Multiplies the tweak by the primitive element α of GF(2^128), using the
+/// little-endian byte order of IEEE 1619.
+fn mul_alpha(tweak: &mut [u8; BLOCKSIZE]) {
+    let t = u128::from_le_bytes(*tweak);
+    let carry = 0u128.wrapping_sub(t >> 127);
+    *tweak = ((t << 1) ^ (carry & 0x87)).to_le_bytes();
+}
@@ -0,0 +1,210 @@
+use std::io::{self, Read, Write};
+
+use crate::aes::{PaddingStrategy, BLOCKSIZE};
+use crate::error::Error;
+use crate::modes::BlockMode;
+
+/// Data is encrypted and decrypted in chunks of up to this many bytes.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Encrypts everything written to it with a [`BlockMode`] and writes the
+/// ciphertext to the inner writer.
+///
+/// Partial blocks are buffered until more data arrives, the padding is only
+/// added by [`Encryptor::finish`], which has to be called to complete the
+/// ciphertext. Blocks are encrypted before they are written, so once the
+/// inner writer fails the stream cannot be resumed and every later write,
+/// flush and finish fails as well.
+pub struct Encryptor<W: Write, M: BlockMode> {
+    inner: W,
+    mode: M,
+    padding: PaddingStrategy,
+    // Plaintext of the current partial block, followed by up to a chunk of
+    // data while a write is processed.
+    buffer: Vec<u8>,
+    failed: bool,
+}
+
+impl<W: Write, M: BlockMode> Encryptor<W, M> {
+    pub fn new(inner: W, mode: M) -> Self {
+        Self::with_padding(inner, mode, Default::default())
+    }
+
+    pub fn with_padding(inner: W, mode: M, padding: PaddingStrategy) -> Self {
+        Self {
+            inner,
+            mode,
+            padding,
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+            failed: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Pads and encrypts the buffered partial block, writes it and returns
+    /// the flushed inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.check_failed()?;
+        self.padding
+            .try_pad(&mut self.buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        self.mode.encrypt_blocks(&mut self.buffer);
+        self.write_encrypted(self.buffer.len())?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Writes the first `len` bytes of the buffer, which have already been
+    /// encrypted. If that fails the buffer holds ciphertext and the mode has
+    /// moved on, so the stream is marked as failed.
+    fn write_encrypted(&mut self, len: usize) -> io::Result<()> {
+        self.inner
+            .write_all(&self.buffer[..len])
+            .inspect_err(|_| self.failed = true)
+    }
+
+    fn check_failed(&self) -> io::Result<()> {
+        if self.failed {
+            return Err(io::Error::other(
+                "a previous write to the inner writer failed",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write, M: BlockMode> Write for Encryptor<W, M> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.check_failed()?;
+        let n = data.len().min(CHUNK_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&data[..n]);
+        let aligned = self.buffer.len() / BLOCKSIZE * BLOCKSIZE;
+        self.mode.encrypt_blocks(&mut self.buffer[..aligned]);
+        self.write_encrypted(aligned)?;
+        self.buffer.drain(..aligned);
+        Ok(n)
+    }
+
+    /// Flushes the inner writer. A buffered partial block stays buffered,
+    /// it can only be written once it is complete or padded.
+    fn flush(&mut self) -> io::Result<()> {
+        self.check_failed()?;
+        self.inner.flush()
+    }
+}
+
+/// Reads ciphertext from the inner reader and decrypts it with a
+/// [`BlockMode`].
+///
+/// The last block is held back until the inner reader reaches EOF, so the
+/// padding is stripped before it is returned. A ciphertext that is not block
+/// aligned or whose padding is malformed fails with
+/// [`io::ErrorKind::InvalidData`] wrapping an [`Error`], also on every later
+/// read.
+pub struct Decryptor<R: Read, M: BlockMode> {
+    inner: R,
+    mode: M,
+    padding: PaddingStrategy,
+    // Ciphertext that has been read but not decrypted yet, at least the
+    // last block until EOF.
+    input: Vec<u8>,
+    // Decrypted plaintext, returned from `pos` onwards.
+    output: Vec<u8>,
+    pos: usize,
+    eof: bool,
+    invalid: bool,
+}
+
+impl<R: Read, M: BlockMode> Decryptor<R, M> {
+    pub fn new(inner: R, mode: M) -> Self {
+        Self::with_padding(inner, mode, Default::default())
+    }
+
+    pub fn with_padding(inner: R, mode: M, padding: PaddingStrategy) -> Self {
+        Self {
+            inner,
+            mode,
+            padding,
+            input: Vec::with_capacity(CHUNK_SIZE + BLOCKSIZE),
+            output: Vec::with_capacity(CHUNK_SIZE + BLOCKSIZE),
+            pos: 0,
+            eof: false,
+            invalid: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads and decrypts until there is plaintext to return or the
+    /// ciphertext is exhausted.
+    fn fill_output(&mut self) -> io::Result<()> {
+        while self.pos == self.output.len() && !self.eof {
+            let start = self.input.len();
+            self.input.resize(start + CHUNK_SIZE, 0);
+            let n = match self.inner.read(&mut self.input[start..]) {
+                Ok(n) => n,
+                Err(e) => {
+                    self.input.truncate(start);
+                    return Err(e);
+                }
+            };
+            self.input.truncate(start + n);
+
+            let len = if n == 0 {
+                self.eof = true;
+                if !self.input.len().is_multiple_of(BLOCKSIZE) {
+                    return Err(self.invalid_data(Error::NotBlockAligned));
+                }
+                self.input.len()
+            } else {
+                self.input.len().saturating_sub(1) / BLOCKSIZE * BLOCKSIZE
+            };
+            self.mode.decrypt_blocks(&mut self.input[..len]);
+            self.output.clear();
+            self.output.extend(self.input.drain(..len));
+            self.pos = 0;
+
+            if self.eof {
+                let Some(len) = self.padding.unpadded_len(&self.output) else {
+                    return Err(self.invalid_data(Error::InvalidPadding));
+                };
+                self.output.truncate(len);
+            }
+        }
+        Ok(())
+    }
+
+    /// Discards everything that has not been returned yet and fails all
+    /// further reads.
+    fn invalid_data(&mut self, error: Error) -> io::Error {
+        self.invalid = true;
+        self.output.clear();
+        self.pos = 0;
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+impl<R: Read, M: BlockMode> Read for Decryptor<R, M> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.invalid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "ciphertext is invalid",
+            ));
+        }
+        self.fill_output()?;
+        let n = buf.len().min(self.output.len() - self.pos);
+        buf[..n].copy_from_slice(&self.output[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
@@ -0,0 +1,29 @@
+use crate::aes::BLOCKSIZE;
+
+pub mod ghash;
+pub mod polyval;
+
+pub use ghash::Ghash;
+pub use polyval::Polyval;
+
+/// A polynomial hash over GF(2^128) keyed with a secret 16 byte block.
+///
+/// Universal hashes are not MACs on their own: the output is only safe to
+/// reveal after masking it, as GCM, GMAC and GCM-SIV do. A key must not be
+/// used for more than one masked message.
+pub trait UniversalHash {
+    fn new(key: &[u8; BLOCKSIZE]) -> Self;
+
+    fn update_block(&mut self, block: &[u8; BLOCKSIZE]);
+
+    /// Absorbs `data`, zero padding the last block if it is partial.
+    fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(BLOCKSIZE) {
+            let mut block = [0; BLOCKSIZE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(&block);
+        }
+    }
+
+    fn finalize(self) -> [u8; BLOCKSIZE];
+}
@@ -0,0 +1,56 @@
+use crate::aes::BLOCKSIZE;
+use crate::universal_hash::UniversalHash;
+
+/// The GHASH universal hash from the GCM specification.
+///
+/// Field elements use GCM's reflected bit order: the most significant bit of
+/// the big-endian `u128` is the coefficient of x^0.
+#[derive(Clone)]
+pub struct Ghash {
+    h: u128,
+    y: u128,
+}
+
+impl UniversalHash for Ghash {
+    fn new(h: &[u8; BLOCKSIZE]) -> Self {
+        Self {
+            h: u128::from_be_bytes(*h),
+            y: 0,
+        }
+    }
+
+    fn update_block(&mut self, block: &[u8; BLOCKSIZE]) {
+        self.y = gf_mul(self.y ^ u128::from_be_bytes(*block), self.h);
+    }
+
+    fn finalize(self) -> [u8; BLOCKSIZE] {
+        self.y.to_be_bytes()
+    }
+}
+
+/// Multiplication in GF(2^128) modulo x^128 + x^7 + x^2 + x + 1.
+///
+/// Runs a fixed number of iterations and selects with masks instead of
+/// branching on the operands.
+pub(crate) fn gf_mul(x: u128, y: u128) -> u128 {
+    const R: u128 = 0xe1 << 120;
+    let mut z = 0;
+    let mut v = y;
+    for i in (0..128).rev() {
+        let bit = (x >> i) & 1;
+        z ^= v & 0u128.wrapping_sub(bit);
+        let lsb = v & 1;
+        v = (v >> 1) ^ (R & 0u128.wrapping_sub(lsb));
+    }
+    z
+}
+
+#[test]
+fn test_gf_mul_identity() {
+    // x^0 is the most significant bit in GCM's bit order.
+    let one = 1u128 << 127;
+    let a = 0x66e94bd4ef8a2c3b884cfa59ca342b2e;
+    assert_eq!(gf_mul(a, one), a);
+    assert_eq!(gf_mul(one, a), a);
+    assert_eq!(gf_mul(a, 0), 0);
+}
@@ -0,0 +1,44 @@
+use crate::aes::BLOCKSIZE;
+use crate::universal_hash::ghash::gf_mul;
+use crate::universal_hash::UniversalHash;
+
+/// The POLYVAL universal hash from RFC 8452.
+///
+/// POLYVAL is GHASH with the bytes of every block reversed, so it is
+/// computed with GHASH's field arithmetic on a transformed key, see RFC 8452
+/// Appendix A.
+#[derive(Clone)]
+pub struct Polyval {
+    h: u128,
+    y: u128,
+}
+
+impl UniversalHash for Polyval {
+    fn new(h: &[u8; BLOCKSIZE]) -> Self {
+        // Reading little-endian is ByteReverse followed by GHASH's big-endian
+        // view, the key additionally gets multiplied by x.
+        let h = u128::from_le_bytes(*h);
+        let h = (h >> 1) ^ ((0xe1 << 120) & 0u128.wrapping_sub(h & 1));
+        Self { h, y: 0 }
+    }
+
+    fn update_block(&mut self, block: &[u8; BLOCKSIZE]) {
+        self.y = gf_mul(self.y ^ u128::from_le_bytes(*block), self.h);
+    }
+
+    fn finalize(self) -> [u8; BLOCKSIZE] {
+        self.y.to_le_bytes()
+    }
+}
+
+#[test]
+fn test_polyval_rfc8452_example() {
+    // RFC 8452, Appendix A
+    let mut polyval = Polyval::new(&0x25629347589242761d31f826ba4b757bu128.to_be_bytes());
+    polyval.update_block(&0x4f4f95668c83dfb6401762bb2d01a262u128.to_be_bytes());
+    polyval.update_block(&0xd1a24ddd2721d006bbe45f20d3c9f362u128.to_be_bytes());
+    assert_eq!(
+        polyval.finalize(),
+        0xf7a3b47b846119fae5b7866cf5e5b77eu128.to_be_bytes()
+    );
+}
@@ -0,0 +1,43 @@
+use crate::aes::BLOCKSIZE;
+
+#[inline(always)]
+pub(crate) fn xor_in_place(dst: &mut [u8], src: &[u8]) {
+    debug_assert!(dst.len() <= src.len());
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d ^= s;
+    }
+}
+
+#[inline(always)]
+pub(crate) fn as_block(chunk: &mut [u8]) -> &mut [u8; BLOCKSIZE] {
+    chunk.try_into().expect("chunk has to be exactly one block")
+}
+
+/// Compares two slices without exiting early on the first difference.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+/// 0xff if `a == b` and 0 otherwise, computed without branching.
+#[inline(always)]
+pub(crate) fn ct_eq_mask(a: u8, b: u8) -> u8 {
+    ((((a ^ b) as u32).wrapping_sub(1)) >> 8) as u8
+}
+
+/// 0xff if `a <= b` and 0 otherwise, computed without branching.
+#[inline(always)]
+pub(crate) fn ct_le_mask(a: u8, b: u8) -> u8 {
+    !(((b as u32).wrapping_sub(a as u32)) >> 8) as u8
+}
+
+/// Doubling in GF(2^128) as used by CMAC, S2V and OCB: a left shift of the
+/// big-endian block, reduced by x^128 + x^7 + x^2 + x + 1.
+#[inline(always)]
+pub(crate) fn dbl(block: &[u8; BLOCKSIZE]) -> [u8; BLOCKSIZE] {
+    let val = u128::from_be_bytes(*block);
+    ((val << 1) ^ (0u128.wrapping_sub(val >> 127) & 0x87)).to_be_bytes()
+}
@@ -0,0 +1,192 @@
+mod common;
+
+#[cfg(test)]
+mod backend_tests {
+    use crate::common::*;
+    use cryptonulz::aead::Gcm;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::Cbc;
+
+    const BACKENDS: [Backend; 4] = [
+        Backend::Reference,
+        Backend::TTable,
+        Backend::Bitsliced,
+        Backend::AesNi,
+    ];
+
+    /// The backend an instance asking for `backend` gets on this CPU.
+    fn effective(backend: Backend) -> Backend {
+        match backend {
+            Backend::AesNi if !has_aesni() => Backend::TTable,
+            backend => backend,
+        }
+    }
+
+    fn has_aesni() -> bool {
+        #[cfg(target_arch = "x86_64")]
+        return std::arch::is_x86_feature_detected!("aes");
+        #[cfg(not(target_arch = "x86_64"))]
+        return false;
+    }
+
+    fn xorshift(state: &mut u64) -> u8 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state as u8
+    }
+
+    fn random_bytes<const N: usize>(state: &mut u64) -> [u8; N] {
+        core::array::from_fn(|_| xorshift(state))
+    }
+
+    // FIPS 197, Appendix C.
+    #[test]
+    fn test_fips197_vectors() {
+        let plaintext = hex_array("00112233445566778899aabbccddeeff");
+        for backend in BACKENDS {
+            let key = hex_array("000102030405060708090a0b0c0d0e0f");
+            let aes = Aes128::with_backend(&key, backend);
+            assert_eq!(aes.backend(), effective(backend));
+            let mut block = plaintext;
+            aes.encrypt_block(&mut block);
+            assert_eq!(block[..], hex("69c4e0d86a7b0430d8cdb78070b4c55a"));
+            aes.decrypt_block(&mut block);
+            assert_eq!(block, plaintext);
+
+            let key = hex_array("000102030405060708090a0b0c0d0e0f1011121314151617");
+            let aes = Aes192::with_backend(&key, backend);
+            let mut block = plaintext;
+            aes.encrypt_block(&mut block);
+            assert_eq!(block[..], hex("dda97ca4864cdfe06eaf70a0ec0d7191"));
+            aes.decrypt_block(&mut block);
+            assert_eq!(block, plaintext);
+
+            let key = hex_array("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+            let aes = Aes256::with_backend(&key, backend);
+            let mut block = plaintext;
+            aes.encrypt_block(&mut block);
+            assert_eq!(block[..], hex("8ea2b7ca516745bfeafc49904b496089"));
+            aes.decrypt_block(&mut block);
+            assert_eq!(block, plaintext);
+        }
+    }
+
+    fn cross_check<C: Cryptoprovider>(reference: &C, other: &C, state: &mut u64) {
+        for _ in 0..64 {
+            let plaintext = random_bytes::<16>(state);
+            let mut expected = plaintext;
+            reference.encrypt_block(&mut expected);
+            let mut block = plaintext;
+            other.encrypt_block(&mut block);
+            assert_eq!(block, expected);
+            other.decrypt_block(&mut block);
+            assert_eq!(block, plaintext);
+        }
+        // Buffers that fill some batches only partly.
+        for len in 0..=9 * 16 {
+            let plaintext: Vec<u8> = (0..len).map(|_| xorshift(state)).collect();
+            let mut expected = plaintext.clone();
+            reference.encrypt(&mut expected);
+            let mut buffer = plaintext.clone();
+            other.encrypt(&mut buffer);
+            assert_eq!(buffer, expected, "{len} bytes");
+            other.decrypt(&mut buffer);
+            assert_eq!(buffer, plaintext);
+        }
+    }
+
+    #[test]
+    fn test_backends_agree_with_reference() {
+        let mut state = 0x9e3779b97f4a7c15;
+        for backend in BACKENDS {
+            for _ in 0..8 {
+                let key = random_bytes(&mut state);
+                let reference = Aes128::with_backend(&key, Backend::Reference);
+                cross_check(&reference, &Aes128::with_backend(&key, backend), &mut state);
+                let key = random_bytes(&mut state);
+                let reference = Aes192::with_backend(&key, Backend::Reference);
+                cross_check(&reference, &Aes192::with_backend(&key, backend), &mut state);
+                let key = random_bytes(&mut state);
+                let reference = Aes256::with_backend(&key, Backend::Reference);
+                cross_check(&reference, &Aes256::with_backend(&key, backend), &mut state);
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_backend() {
+        let expected = effective(Backend::AesNi);
+        assert_eq!(Backend::default(), expected);
+        assert_eq!(Aes128::new(&[0; 16]).backend(), expected);
+        assert_eq!(
+            Aes192::with_padding(&[0; 24], PaddingStrategy::NONE).backend(),
+            expected
+        );
+        assert_eq!(Aes256::new(&[0; 32]).backend(), expected);
+    }
+
+    #[test]
+    fn test_padding_and_backend_together() {
+        let aes = Aes192::with_padding_and_backend(
+            &hex_array(SP800_38A_KEY_192),
+            PaddingStrategy::ISO7816,
+            Backend::Bitsliced,
+        );
+        assert_eq!(aes.backend(), Backend::Bitsliced);
+        let mut data = b"fixed size record".to_vec();
+        aes.try_encrypt(&mut data).unwrap();
+        assert_eq!(data.len(), 32);
+
+        let mut padded = data.clone();
+        Aes192::with_padding(&hex_array(SP800_38A_KEY_192), PaddingStrategy::NONE)
+            .try_decrypt(&mut padded)
+            .unwrap();
+        assert_eq!(padded[17], 0x80);
+        assert!(padded[18..].iter().all(|b| *b == 0));
+
+        aes.try_decrypt(&mut data).unwrap();
+        assert_eq!(data, b"fixed size record");
+    }
+
+    // NIST SP 800-38A, F.2.1 and the GCM spec test case 4, run through the
+    // modes on every backend.
+    #[test]
+    fn test_modes_on_every_backend() {
+        for backend in BACKENDS {
+            let key = hex_array(SP800_38A_KEY_128);
+            let iv = hex_array("000102030405060708090a0b0c0d0e0f");
+            let mut data = hex(SP800_38A_PLAINTEXT);
+            Cbc::new(Aes128::with_backend(&key, backend), &iv).encrypt_blocks(&mut data);
+            assert_eq!(
+                data,
+                hex(
+                    "7649abac8119b246cee98e9b12e9197d 5086cb9b507219ee95db113a917678b2
+                     73bed6b8e3c1743b7116e69e22229516 3ff1caa1681fac09120eca307586e1a7"
+                )
+            );
+
+            let gcm = Gcm::new(Aes128::with_backend(
+                &hex_array("feffe9928665731c6d6a8f9467308308"),
+                backend,
+            ));
+            let mut data = hex(
+                "d9313225f88406e5a55909c5aff5269a 86a7a9531534f7da2e4c303d8a318a72
+                 1c3c0c95956809532fcf0e2449a6b525 b16aedf5aa0de657ba637b39",
+            );
+            let tag = gcm.encrypt_detached(
+                &hex("cafebabefacedbaddecaf888"),
+                &hex("feedfacedeadbeeffeedfacedeadbeefabaddad2"),
+                &mut data,
+            );
+            assert_eq!(
+                data,
+                hex(
+                    "42831ec2217774244b7221b784d0d49c e3aa212f2c02a4e035c17e2329aca12e
+                     21d514b25466931c7d8f6a5aac84aa05 1ba30b396a0aac973d58e091"
+                )
+            );
+            assert_eq!(tag, hex("5bc94fbc3221a5db94fae95ae7121a47"));
+        }
+    }
+}
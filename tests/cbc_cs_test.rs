@@ -0,0 +1,149 @@
+mod common;
+
+#[cfg(test)]
+mod cbc_cs_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::{Cbc, CbcCs, CsVariant};
+
+    // Vectors from RFC 3962, Appendix B, which uses CS3 with a zero iv.
+    const KEY: &str = "636869636b656e207465726979616b69";
+    const MESSAGE: &[u8] = b"I would like the General Gau's Chicken, please, and wonton soup.";
+
+    fn check(variant: CsVariant, len: usize, ciphertext: &str, next_iv: &str) {
+        let mut data = MESSAGE[..len].to_vec();
+        let mut cbc = CbcCs::new(Aes128::new(&hex_array(KEY)), &[0; 16], variant);
+        cbc.encrypt(&mut data);
+        assert_eq!(data, hex(ciphertext), "{variant:?}, {len} bytes");
+        assert_eq!(cbc.iv()[..], hex(next_iv));
+
+        let mut cbc = CbcCs::new(Aes128::new(&hex_array(KEY)), &[0; 16], variant);
+        cbc.decrypt(&mut data);
+        assert_eq!(data, &MESSAGE[..len]);
+        assert_eq!(cbc.iv()[..], hex(next_iv));
+    }
+
+    #[test]
+    fn test_cs3_rfc3962_vectors() {
+        check(
+            CsVariant::CS3,
+            17,
+            "c6353568f2bf8cb4d8a580362da7ff7f 97",
+            "c6353568f2bf8cb4d8a580362da7ff7f",
+        );
+        check(
+            CsVariant::CS3,
+            31,
+            "fc00783e0efdb2c1d445d4c8eff7ed22 97687268d6ecccc0c07b25e25ecfe5",
+            "fc00783e0efdb2c1d445d4c8eff7ed22",
+        );
+        check(
+            CsVariant::CS3,
+            32,
+            "39312523a78662d5be7fcbcc98ebf5a8 97687268d6ecccc0c07b25e25ecfe584",
+            "39312523a78662d5be7fcbcc98ebf5a8",
+        );
+        check(
+            CsVariant::CS3,
+            47,
+            "97687268d6ecccc0c07b25e25ecfe584 b3fffd940c16a18c1b5549d2f838029e
+             39312523a78662d5be7fcbcc98ebf5",
+            "b3fffd940c16a18c1b5549d2f838029e",
+        );
+        check(
+            CsVariant::CS3,
+            48,
+            "97687268d6ecccc0c07b25e25ecfe584 9dad8bbb96c4cdc03bc103e1a194bbd8
+             39312523a78662d5be7fcbcc98ebf5a8",
+            "9dad8bbb96c4cdc03bc103e1a194bbd8",
+        );
+        check(
+            CsVariant::CS3,
+            64,
+            "97687268d6ecccc0c07b25e25ecfe584 39312523a78662d5be7fcbcc98ebf5a8
+             4807efe836ee89a526730dbc2f7bc840 9dad8bbb96c4cdc03bc103e1a194bbd8",
+            "4807efe836ee89a526730dbc2f7bc840",
+        );
+    }
+
+    // CS1 and CS2 rearrange the same blocks as CS3.
+    #[test]
+    fn test_cs1_ordering() {
+        check(
+            CsVariant::CS1,
+            17,
+            "97 c6353568f2bf8cb4d8a580362da7ff7f",
+            "c6353568f2bf8cb4d8a580362da7ff7f",
+        );
+        check(
+            CsVariant::CS1,
+            47,
+            "97687268d6ecccc0c07b25e25ecfe584 39312523a78662d5be7fcbcc98ebf5
+             b3fffd940c16a18c1b5549d2f838029e",
+            "b3fffd940c16a18c1b5549d2f838029e",
+        );
+        check(
+            CsVariant::CS1,
+            48,
+            "97687268d6ecccc0c07b25e25ecfe584 39312523a78662d5be7fcbcc98ebf5a8
+             9dad8bbb96c4cdc03bc103e1a194bbd8",
+            "9dad8bbb96c4cdc03bc103e1a194bbd8",
+        );
+    }
+
+    #[test]
+    fn test_cs2_ordering() {
+        check(
+            CsVariant::CS2,
+            31,
+            "fc00783e0efdb2c1d445d4c8eff7ed22 97687268d6ecccc0c07b25e25ecfe5",
+            "fc00783e0efdb2c1d445d4c8eff7ed22",
+        );
+        check(
+            CsVariant::CS2,
+            32,
+            "97687268d6ecccc0c07b25e25ecfe584 39312523a78662d5be7fcbcc98ebf5a8",
+            "39312523a78662d5be7fcbcc98ebf5a8",
+        );
+    }
+
+    #[test]
+    fn test_aligned_cs1_and_cs2_match_cbc() {
+        let key = hex_array(SP800_38A_KEY_128);
+        let iv = hex_array("000102030405060708090a0b0c0d0e0f");
+        let plaintext = hex(SP800_38A_PLAINTEXT);
+        let mut expected = plaintext.clone();
+        Cbc::new(Aes128::new(&key), &iv).encrypt_blocks(&mut expected);
+        for variant in [CsVariant::CS1, CsVariant::CS2] {
+            for len in [16, 32, 64] {
+                let mut data = plaintext[..len].to_vec();
+                CbcCs::new(Aes128::new(&key), &iv, variant).encrypt(&mut data);
+                assert_eq!(data, expected[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_keeps_length() {
+        let key = hex_array(SP800_38A_KEY_256);
+        let iv = [0x24; 16];
+        for variant in [CsVariant::CS1, CsVariant::CS2, CsVariant::CS3] {
+            for len in 16..=80 {
+                let plaintext: Vec<u8> = (0..len as u8).collect();
+                let mut data = plaintext.clone();
+                CbcCs::new(Aes256::new(&key), &iv, variant).encrypt(&mut data);
+                assert_eq!(data.len(), len);
+                assert_ne!(data, plaintext);
+                CbcCs::new(Aes256::new(&key), &iv, variant).decrypt(&mut data);
+                assert_eq!(data, plaintext, "{variant:?}, {len} bytes");
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ciphertext stealing needs at least one block")]
+    fn test_short_message_panics() {
+        let mut data = [0; 15];
+        CbcCs::new(Aes128::new(&[0; 16]), &[0; 16], CsVariant::CS3).encrypt(&mut data);
+    }
+}
@@ -0,0 +1,91 @@
+mod common;
+
+#[cfg(test)]
+mod cbc_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::Cbc;
+
+    const IV: &str = "000102030405060708090a0b0c0d0e0f";
+
+    fn check_sp800_38a<C: Cryptoprovider>(cipher: C, ciphertext: &str) {
+        let iv = hex_array(IV);
+        let mut data = hex(SP800_38A_PLAINTEXT);
+        let mut cbc = Cbc::new(&cipher, &iv);
+        cbc.encrypt_blocks(&mut data);
+        assert_eq!(data, hex(ciphertext));
+        assert_eq!(&cbc.iv()[..], &data[data.len() - BLOCKSIZE..]);
+
+        Cbc::new(&cipher, &iv).decrypt_blocks(&mut data);
+        assert_eq!(data, hex(SP800_38A_PLAINTEXT));
+    }
+
+    #[test]
+    fn test_cbc_aes128_sp800_38a() {
+        check_sp800_38a(
+            Aes128::new(&hex_array(SP800_38A_KEY_128)),
+            "7649abac8119b246cee98e9b12e9197d 5086cb9b507219ee95db113a917678b2
+             73bed6b8e3c1743b7116e69e22229516 3ff1caa1681fac09120eca307586e1a7",
+        );
+    }
+
+    #[test]
+    fn test_cbc_aes192_sp800_38a() {
+        check_sp800_38a(
+            Aes192::new(&hex_array(SP800_38A_KEY_192)),
+            "4f021db243bc633d7178183a9fa071e8 b4d9ada9ad7dedf4e5e738763f69145a
+             571b242012fb7ae07fa9baac3df102e0 08b0e27988598881d920a9e64f5615cd",
+        );
+    }
+
+    #[test]
+    fn test_cbc_aes256_sp800_38a() {
+        check_sp800_38a(
+            Aes256::new(&hex_array(SP800_38A_KEY_256)),
+            "f58c4c04d6e5f1ba779eabfb5f7bfbd6 9cfc4e967edb808d679f777bc6702c7d
+             39f23369a9d9bacfa530e26304231461 b2eb05e2c39be9fcda6c19078c6a9d1b",
+        );
+    }
+
+    #[test]
+    fn test_cbc_chaining_across_calls() {
+        let aes = Aes128::new(&hex_array(SP800_38A_KEY_128));
+        let iv = hex_array(IV);
+        let mut whole = hex(SP800_38A_PLAINTEXT);
+        Cbc::new(&aes, &iv).encrypt_blocks(&mut whole);
+
+        let mut split = hex(SP800_38A_PLAINTEXT);
+        let mut cbc = Cbc::new(&aes, &iv);
+        let (head, tail) = split.split_at_mut(BLOCKSIZE);
+        cbc.encrypt_blocks(head);
+        cbc.encrypt_blocks(tail);
+        assert_eq!(split, whole);
+    }
+
+    #[test]
+    fn test_cbc_with_padding() {
+        let key = b"TopSecretPasswor";
+        let iv = b"NotSoRandomIv!!!";
+        let aes = Aes128::new(key);
+        let mut data = b"This is a super secret text that no one should read!".to_vec();
+        Cbc::new(&aes, iv).encrypt(&mut data);
+        assert_eq!(data.len(), 64);
+        assert_ne!(
+            &data[..52],
+            b"This is a super secret text that no one should read!"
+        );
+        Cbc::new(&aes, iv).decrypt(&mut data);
+        assert_eq!(
+            data,
+            b"This is a super secret text that no one should read!"
+        );
+    }
+
+    #[test]
+    fn test_cbc_hides_repeated_blocks() {
+        let aes = Aes128::new(b"TopSecretPasswor");
+        let mut data = b"NulzIstEinHund!!NulzIstEinHund!!".to_vec();
+        Cbc::with_padding(&aes, b"NotSoRandomIv!!!", PaddingStrategy::PKCS7).encrypt(&mut data);
+        assert_ne!(data[..BLOCKSIZE], data[BLOCKSIZE..2 * BLOCKSIZE]);
+    }
+}
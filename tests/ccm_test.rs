@@ -0,0 +1,216 @@
+mod common;
+
+#[cfg(test)]
+mod ccm_tests {
+    use crate::common::*;
+    use cryptonulz::aead::{Ccm, Error};
+    use cryptonulz::aes::*;
+
+    fn counting(range: std::ops::Range<u8>) -> Vec<u8> {
+        range.collect()
+    }
+
+    fn check<C: Cryptoprovider>(
+        ccm: &Ccm<C>,
+        nonce: &str,
+        aad: &[u8],
+        plaintext: &[u8],
+        sealed: &str,
+    ) {
+        let nonce = hex(nonce);
+        let sealed = hex(sealed);
+        let mut data = plaintext.to_vec();
+        ccm.encrypt(&nonce, aad, &mut data);
+        assert_eq!(data, sealed);
+        ccm.decrypt(&nonce, aad, &mut data).unwrap();
+        assert_eq!(data, plaintext);
+
+        let mut data = plaintext.to_vec();
+        let tag = ccm.encrypt_detached(&nonce, aad, &mut data);
+        assert_eq!(tag, sealed[plaintext.len()..]);
+        ccm.decrypt_detached(&nonce, aad, &mut data, &tag).unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn test_ccm_rfc3610_packets() {
+        let aes = Aes128::new(&hex_array("c0c1c2c3c4c5c6c7c8c9cacbcccdcecf"));
+        let header = counting(0..8);
+        let ccm = Ccm::new(&aes, 13, 8);
+        // Packet Vector #1
+        check(
+            &ccm,
+            "00000003020100a0a1a2a3a4a5",
+            &header,
+            &counting(8..31),
+            "588c979a61c663d2f066d0c2c0f989806d5f6b61dac384 17e8d12cfdf926e0",
+        );
+        // Packet Vector #2
+        check(
+            &ccm,
+            "00000004030201a0a1a2a3a4a5",
+            &header,
+            &counting(8..32),
+            "72c91a36e135f8cf291ca894085c87e3cc15c439c9e43a3b a091d56e10400916",
+        );
+        // Packet Vector #3
+        check(
+            &ccm,
+            "00000005040302a0a1a2a3a4a5",
+            &header,
+            &counting(8..33),
+            "51b1e5f44a197d1da46b0f8e2d282ae871e838bb64da859657 4adaa76fbd9fb0c5",
+        );
+        // Packet Vector #7
+        check(
+            &Ccm::new(&aes, 13, 10),
+            "00000009080706a0a1a2a3a4a5",
+            &header,
+            &counting(8..31),
+            "0135d1b2c95f41d5d1d4fec185d166b8094e999dfed96c 048c56602c97acbb7490",
+        );
+    }
+
+    #[test]
+    fn test_ccm_sp800_38c_examples() {
+        let aes = Aes128::new(&hex_array("404142434445464748494a4b4c4d4e4f"));
+        check(
+            &Ccm::new(&aes, 7, 4),
+            "10111213141516",
+            &counting(0..8),
+            &counting(0x20..0x24),
+            "7162015b 4dac255d",
+        );
+        check(
+            &Ccm::new(&aes, 8, 6),
+            "1011121314151617",
+            &counting(0..16),
+            &counting(0x20..0x30),
+            "d2a1f0e051ea5f62081a7792073d593d 1fc64fbfaccd",
+        );
+        check(
+            &Ccm::new(&aes, 12, 8),
+            "101112131415161718191a1b",
+            &counting(0..20),
+            &counting(0x20..0x38),
+            "e3b201a9f5b71a7a9b1ceaeccd97e70b6176aad9a4428aa5 484392fbc1b09951",
+        );
+    }
+
+    #[test]
+    fn test_ccm_long_aad_encoding() {
+        // 2^16 bytes of associated data need the 0xfffe length prefix.
+        let aes = Aes128::new(&hex_array("404142434445464748494a4b4c4d4e4f"));
+        let aad: Vec<u8> = (0..=255).cycle().take(1 << 16).collect();
+        check(
+            &Ccm::new(&aes, 12, 14),
+            "101112131415161718191a1b",
+            &aad,
+            &counting(0x20..0x40),
+            "e3b201a9f5b71a7a9b1ceaeccd97e70b6176aad9a4428aa5541bd1d416fa0ce3
+             c613795fb1bdce038a918e674758",
+        );
+    }
+
+    #[test]
+    fn test_ccm_other_key_sizes() {
+        let nonce = "101112131415161718191a1b";
+        let aes = Aes192::new(&hex_array(
+            "000102030405060708090a0b0c0d0e0f1011121314151617",
+        ));
+        check(
+            &Ccm::new(&aes, 12, 16),
+            nonce,
+            &counting(0..20),
+            &counting(0x20..0x38),
+            "f3ccb83cb71093d65d714647e6085b7b435b76ff750392ee
+             7f03e8f4620260527707749bbc566b37",
+        );
+        let aes = Aes256::new(&hex_array(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ));
+        check(
+            &Ccm::new(&aes, 12, 16),
+            nonce,
+            &counting(0..20),
+            &counting(0x20..0x38),
+            "5191d320426e464744dab74510729e1e06c5951ed131033e
+             7319afc91fafa6c4ccd5e77164f12321",
+        );
+    }
+
+    #[test]
+    fn test_ccm_rejects_tampering() {
+        let aes = Aes128::new(&hex_array("c0c1c2c3c4c5c6c7c8c9cacbcccdcecf"));
+        let ccm = Ccm::new(&aes, 13, 8);
+        let nonce = hex("00000003020100a0a1a2a3a4a5");
+        let mut sealed = counting(8..31);
+        ccm.encrypt(&nonce, &counting(0..8), &mut sealed);
+
+        let mut flipped = sealed.clone();
+        flipped[0] ^= 1;
+        assert_eq!(
+            ccm.decrypt(&nonce, &counting(0..8), &mut flipped),
+            Err(Error)
+        );
+        let mut expected = sealed.clone();
+        expected[0] ^= 1;
+        assert_eq!(flipped, expected);
+
+        let mut bad_aad = sealed.clone();
+        assert_eq!(
+            ccm.decrypt(&nonce, &counting(0..7), &mut bad_aad),
+            Err(Error)
+        );
+
+        let mut truncated = sealed[..sealed.len() - 1].to_vec();
+        assert_eq!(
+            ccm.decrypt(&nonce, &counting(0..8), &mut truncated),
+            Err(Error)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ccm_rejects_odd_tag_length() {
+        Ccm::new(Aes128::new(&[0; 16]), 13, 5);
+    }
+
+    #[test]
+    fn test_ccm_max_len() {
+        let aes = Aes128::new(&[0; 16]);
+        assert_eq!(Ccm::new(&aes, 13, 16).max_len(), 0xffff);
+        assert_eq!(Ccm::new(&aes, 12, 16).max_len(), 0xff_ffff);
+        assert_eq!(Ccm::new(&aes, 7, 16).max_len(), u64::MAX);
+    }
+
+    #[test]
+    fn test_ccm_decrypt_rejects_bad_nonce_and_length() {
+        let aes = Aes128::new(&[0; 16]);
+        let ccm = Ccm::new(&aes, 13, 16);
+        let mut sealed = counting(0..40);
+        ccm.encrypt(&[7; 13], b"", &mut sealed);
+        let copy = sealed.clone();
+        for nonce_len in [0, 7, 12, 14] {
+            assert_eq!(
+                ccm.decrypt(&vec![7; nonce_len], b"", &mut sealed),
+                Err(Error)
+            );
+            assert_eq!(sealed, copy);
+        }
+
+        let mut overlong = vec![0; 0x10000];
+        assert_eq!(
+            ccm.decrypt_detached(&[7; 13], b"", &mut overlong, &[0; 16]),
+            Err(Error)
+        );
+        assert!(overlong.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong CCM nonce length")]
+    fn test_ccm_encrypt_with_wrong_nonce_length_panics() {
+        let ccm = Ccm::new(Aes128::new(&[0; 16]), 13, 16);
+        ccm.encrypt(&[7; 12], b"", &mut vec![0; 16]);
+    }
+}
@@ -0,0 +1,106 @@
+mod common;
+
+#[cfg(test)]
+mod cfb_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::{Cfb, SegmentSize};
+
+    const IV: &str = "000102030405060708090a0b0c0d0e0f";
+
+    fn check<C: Cryptoprovider>(cipher: C, segment_size: SegmentSize, ciphertext: &str) {
+        let iv = hex_array(IV);
+        let expected = hex(ciphertext);
+        let plaintext = &hex(SP800_38A_PLAINTEXT)[..expected.len()];
+
+        let mut data = plaintext.to_vec();
+        Cfb::with_segment_size(&cipher, &iv, segment_size).encrypt(&mut data);
+        assert_eq!(data, expected);
+        Cfb::with_segment_size(&cipher, &iv, segment_size).decrypt(&mut data);
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn test_cfb1_sp800_38a() {
+        // The 16 bit example messages of F.3.1, F.3.3 and F.3.5.
+        check(
+            Aes128::new(&hex_array(SP800_38A_KEY_128)),
+            SegmentSize::Cfb1,
+            "68b3",
+        );
+        check(
+            Aes192::new(&hex_array(SP800_38A_KEY_192)),
+            SegmentSize::Cfb1,
+            "9359",
+        );
+        check(
+            Aes256::new(&hex_array(SP800_38A_KEY_256)),
+            SegmentSize::Cfb1,
+            "9029",
+        );
+    }
+
+    #[test]
+    fn test_cfb8_sp800_38a() {
+        check(
+            Aes128::new(&hex_array(SP800_38A_KEY_128)),
+            SegmentSize::Cfb8,
+            "3b79424c9c0dd436bace9e0ed4586a4f32b9",
+        );
+        check(
+            Aes192::new(&hex_array(SP800_38A_KEY_192)),
+            SegmentSize::Cfb8,
+            "cda2521ef0a905ca44cd057cbf0d47a0678a",
+        );
+        check(
+            Aes256::new(&hex_array(SP800_38A_KEY_256)),
+            SegmentSize::Cfb8,
+            "dc1f1a8520a64db55fcc8ac554844e889700",
+        );
+    }
+
+    #[test]
+    fn test_cfb128_sp800_38a() {
+        check(
+            Aes128::new(&hex_array(SP800_38A_KEY_128)),
+            SegmentSize::Cfb128,
+            "3b3fd92eb72dad20333449f8e83cfb4a c8a64537a0b3a93fcde3cdad9f1ce58b
+             26751f67a3cbb140b1808cf187a4f4df c04b05357c5d1c0eeac4c66f9ff7f2e6",
+        );
+        check(
+            Aes192::new(&hex_array(SP800_38A_KEY_192)),
+            SegmentSize::Cfb128,
+            "cdc80d6fddf18cab34c25909c99a4174 67ce7f7f81173621961a2b70171d3d7a
+             2e1e8a1dd59b88b1c8e60fed1efac4c9 c05f9f9ca9834fa042ae8fba584b09ff",
+        );
+        check(
+            Aes256::new(&hex_array(SP800_38A_KEY_256)),
+            SegmentSize::Cfb128,
+            "dc7e84bfda79164b7ecd8486985d3860 39ffed143b28b1c832113c6331e5407b
+             df10132415e54b92a13ed0a8267ae2f9 75a385741ab9cef82031623d55b1e471",
+        );
+    }
+
+    #[test]
+    fn test_cfb_split_across_calls() {
+        let aes = Aes128::new(&hex_array(SP800_38A_KEY_128));
+        let iv = hex_array(IV);
+        for segment_size in [SegmentSize::Cfb1, SegmentSize::Cfb8, SegmentSize::Cfb128] {
+            let mut whole = hex(SP800_38A_PLAINTEXT);
+            Cfb::with_segment_size(&aes, &iv, segment_size).encrypt(&mut whole);
+
+            let mut split = hex(SP800_38A_PLAINTEXT);
+            let mut cfb = Cfb::with_segment_size(&aes, &iv, segment_size);
+            for chunk in split.chunks_mut(5) {
+                cfb.encrypt(chunk);
+            }
+            assert_eq!(split, whole);
+
+            let mut cfb = Cfb::with_segment_size(&aes, &iv, segment_size);
+            for chunk in split.chunks_mut(11) {
+                cfb.decrypt(chunk);
+            }
+            assert_eq!(split, hex(SP800_38A_PLAINTEXT));
+        }
+    }
+}
@@ -0,0 +1,122 @@
+mod common;
+
+#[cfg(test)]
+mod cmac_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::mac::{Cmac, Error};
+
+    // RFC 4493, section 4, for AES-128 and the matching examples of
+    // SP 800-38B, appendix D, for AES-192 and AES-256. Messages are
+    // prefixes of the SP 800-38A plaintext.
+    const LENGTHS: [usize; 4] = [0, 16, 40, 64];
+    const TAGS_128: [&str; 4] = [
+        "bb1d6929e95937287fa37d129b756746",
+        "070a16b46b4d4144f79bdd9dd04a287c",
+        "dfa66747de9ae63030ca32611497c827",
+        "51f0bebf7e3b9d92fc49741779363cfe",
+    ];
+    const TAGS_192: [&str; 4] = [
+        "d17ddf46adaacde531cac483de7a9367",
+        "9e99a7bf31e710900662f65e617c5184",
+        "8a1de5be2eb31aad089a82e6ee908b0e",
+        "a1d5df0eed790f794d77589659f39a11",
+    ];
+    const TAGS_256: [&str; 4] = [
+        "028962f61b7bf89efc6b551f4667d983",
+        "28a7023f452e8f82bd4bf28d8c37c35c",
+        "aaf3d8f1de5640c232f5b169b9c911e6",
+        "e1992190549f6ed5696a2c056c315410",
+    ];
+
+    fn check<C: Cryptoprovider>(mut cmac: Cmac<C>, tags: [&str; 4]) {
+        let message = hex(SP800_38A_PLAINTEXT);
+        for (len, tag) in LENGTHS.into_iter().zip(tags) {
+            cmac.update(&message[..len]);
+            assert_eq!(cmac.finalize(), hex(tag));
+            cmac.update(&message[..len]);
+            assert_eq!(cmac.verify(&hex(tag)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_cmac_aes128_rfc4493() {
+        check(
+            Cmac::new(Aes128::new(&hex_array(SP800_38A_KEY_128))),
+            TAGS_128,
+        );
+    }
+
+    #[test]
+    fn test_cmac_aes192() {
+        check(
+            Cmac::new(Aes192::new(&hex_array(SP800_38A_KEY_192))),
+            TAGS_192,
+        );
+    }
+
+    #[test]
+    fn test_cmac_aes256() {
+        check(
+            Cmac::new(Aes256::new(&hex_array(SP800_38A_KEY_256))),
+            TAGS_256,
+        );
+    }
+
+    #[test]
+    fn test_cmac_incremental_update() {
+        let message = hex(SP800_38A_PLAINTEXT);
+        let mut cmac = Cmac::new(Aes128::new(&hex_array(SP800_38A_KEY_128)));
+        for split in 0..=40 {
+            cmac.update(&message[..split]);
+            cmac.update(&[]);
+            cmac.update(&message[split..40]);
+            assert_eq!(cmac.finalize(), hex(TAGS_128[2]));
+        }
+        for byte in &message {
+            cmac.update(std::slice::from_ref(byte));
+        }
+        assert_eq!(cmac.finalize(), hex(TAGS_128[3]));
+
+        cmac.update(b"discarded");
+        cmac.reset();
+        assert_eq!(cmac.finalize(), hex(TAGS_128[0]));
+    }
+
+    #[test]
+    fn test_cmac_truncated_tag() {
+        let message = hex(SP800_38A_PLAINTEXT);
+        let mut cmac = Cmac::with_tag_len(Aes128::new(&hex_array(SP800_38A_KEY_128)), 8);
+        assert_eq!(cmac.tag_len(), 8);
+        cmac.update(&message);
+        assert_eq!(cmac.finalize(), hex(&TAGS_128[3][..16]));
+        cmac.update(&message);
+        assert_eq!(cmac.verify(&hex(&TAGS_128[3][..16])), Ok(()));
+        // The full tag is not accepted in place of the truncated one.
+        cmac.update(&message);
+        assert_eq!(cmac.verify(&hex(TAGS_128[3])), Err(Error));
+    }
+
+    #[test]
+    fn test_cmac_verify_rejects_wrong_tag() {
+        let message = hex(SP800_38A_PLAINTEXT);
+        let mut cmac = Cmac::new(Aes256::new(&hex_array(SP800_38A_KEY_256)));
+        let tag = hex(TAGS_256[3]);
+        for i in 0..tag.len() {
+            let mut wrong = tag.clone();
+            wrong[i] ^= 1;
+            cmac.update(&message);
+            assert_eq!(cmac.verify(&wrong), Err(Error));
+        }
+        cmac.update(&message);
+        assert_eq!(cmac.verify(&tag[..15]), Err(Error));
+        cmac.update(&message[..63]);
+        assert_eq!(cmac.verify(&tag), Err(Error));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cmac_rejects_empty_tag() {
+        Cmac::with_tag_len(Aes128::new(&[0; 16]), 0);
+    }
+}
@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+
+/// Keys and plaintext shared by the NIST SP 800-38A example vectors.
+pub const SP800_38A_KEY_128: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+pub const SP800_38A_KEY_192: &str = "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b";
+pub const SP800_38A_KEY_256: &str =
+    "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+pub const SP800_38A_PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172a
+                                       ae2d8a571e03ac9c9eb76fac45af8e51
+                                       30c81c46a35ce411e5fbc1191a0a52ef
+                                       f69f2445df4f9b17ad2b417be66c3710";
+
+pub fn hex(s: &str) -> Vec<u8> {
+    let s: String = s.split_whitespace().collect();
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+pub fn hex_array<const N: usize>(s: &str) -> [u8; N] {
+    hex(s).try_into().unwrap()
+}
@@ -0,0 +1,117 @@
+mod common;
+
+#[cfg(test)]
+mod ctr_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::ctr::CounterOverflow;
+    use cryptonulz::modes::{CounterSize, Ctr};
+
+    const INITIAL_COUNTER: &str = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+
+    fn check_sp800_38a<C: Cryptoprovider>(cipher: C, ciphertext: &str) {
+        let initial = hex_array(INITIAL_COUNTER);
+        for size in [CounterSize::U32, CounterSize::U64, CounterSize::U128] {
+            let mut data = hex(SP800_38A_PLAINTEXT);
+            let mut ctr = Ctr::with_counter_size(&cipher, &initial, size);
+            ctr.apply_keystream(&mut data).unwrap();
+            assert_eq!(data, hex(ciphertext));
+            assert_eq!(ctr.position(), 64);
+
+            let mut ctr = Ctr::with_counter_size(&cipher, &initial, size);
+            ctr.apply_keystream(&mut data).unwrap();
+            assert_eq!(data, hex(SP800_38A_PLAINTEXT));
+        }
+    }
+
+    #[test]
+    fn test_ctr_aes128_sp800_38a() {
+        check_sp800_38a(
+            Aes128::new(&hex_array(SP800_38A_KEY_128)),
+            "874d6191b620e3261bef6864990db6ce 9806f66b7970fdff8617187bb9fffdff
+             5ae4df3edbd5d35e5b4f09020db03eab 1e031dda2fbe03d1792170a0f3009cee",
+        );
+    }
+
+    #[test]
+    fn test_ctr_aes192_sp800_38a() {
+        check_sp800_38a(
+            Aes192::new(&hex_array(SP800_38A_KEY_192)),
+            "1abc932417521ca24f2b0459fe7e6e0b 090339ec0aa6faefd5ccc2c6f4ce8e94
+             1e36b26bd1ebc670d1bd1d665620abf7 4f78a7f6d29809585a97daec58c6b050",
+        );
+    }
+
+    #[test]
+    fn test_ctr_aes256_sp800_38a() {
+        check_sp800_38a(
+            Aes256::new(&hex_array(SP800_38A_KEY_256)),
+            "601ec313775789a5b7a7f504bbf3d228 f443e3ca4d62b59aca84e990cacaf5c5
+             2b0930daa23de94ce87017ba2d84988d dfc9c58db67aada613c2dd08457941a6",
+        );
+    }
+
+    #[test]
+    fn test_ctr_unaligned_calls() {
+        let aes = Aes128::new(&hex_array(SP800_38A_KEY_128));
+        let initial = hex_array(INITIAL_COUNTER);
+        let mut whole = hex(SP800_38A_PLAINTEXT);
+        Ctr::new(&aes, &initial)
+            .apply_keystream(&mut whole)
+            .unwrap();
+
+        let mut split = hex(SP800_38A_PLAINTEXT);
+        let mut ctr = Ctr::new(&aes, &initial);
+        for chunk in split.chunks_mut(7) {
+            ctr.apply_keystream(chunk).unwrap();
+        }
+        assert_eq!(split, whole);
+    }
+
+    #[test]
+    fn test_ctr_seek() {
+        let aes = Aes128::new(&hex_array(SP800_38A_KEY_128));
+        let initial = hex_array(INITIAL_COUNTER);
+        let mut whole = hex(SP800_38A_PLAINTEXT);
+        Ctr::new(&aes, &initial)
+            .apply_keystream(&mut whole)
+            .unwrap();
+
+        let mut ctr = Ctr::new(&aes, &initial);
+        let mut tail = hex(SP800_38A_PLAINTEXT)[37..].to_vec();
+        ctr.seek(37).unwrap();
+        ctr.apply_keystream(&mut tail).unwrap();
+        assert_eq!(tail, whole[37..]);
+
+        let mut head = hex(SP800_38A_PLAINTEXT)[5..20].to_vec();
+        ctr.seek(5).unwrap();
+        ctr.apply_keystream(&mut head).unwrap();
+        assert_eq!(head, whole[5..20]);
+        assert_eq!(ctr.position(), 20);
+    }
+
+    #[test]
+    fn test_ctr_counter_overflow() {
+        let aes = Aes128::new(&hex_array(SP800_38A_KEY_128));
+        let initial = hex_array("000102030405060708090a0bfffffffe");
+        let mut ctr = Ctr::with_counter_size(&aes, &initial, CounterSize::U32);
+        let mut data = [0u8; 3 * BLOCKSIZE];
+        assert_eq!(ctr.apply_keystream(&mut data), Err(CounterOverflow));
+        assert_eq!(data, [0u8; 3 * BLOCKSIZE]);
+        ctr.apply_keystream(&mut data[..2 * BLOCKSIZE]).unwrap();
+        assert_eq!(ctr.apply_keystream(&mut data[..1]), Err(CounterOverflow));
+        assert_eq!(ctr.seek(2 * BLOCKSIZE as u64 + 1), Err(CounterOverflow));
+
+        // The same counter block carries into the upper 32 bits with a wider
+        // counter instead of wrapping.
+        let mut wide = [0u8; 3 * BLOCKSIZE];
+        Ctr::with_counter_size(&aes, &initial, CounterSize::U64)
+            .apply_keystream(&mut wide)
+            .unwrap();
+        let mut last = [0u8; BLOCKSIZE];
+        Ctr::new(&aes, &hex_array("000102030405060708090a0c00000000"))
+            .apply_keystream(&mut last)
+            .unwrap();
+        assert_eq!(wide[2 * BLOCKSIZE..], last);
+    }
+}
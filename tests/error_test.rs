@@ -0,0 +1,204 @@
+mod common;
+
+#[cfg(test)]
+mod error_tests {
+    use crate::common::*;
+    use cryptonulz::aead::{self, Gcm};
+    use cryptonulz::aes::*;
+    use cryptonulz::keywrap::{KeyWrap, UnwrapError};
+    use cryptonulz::mac::{self, Cmac};
+    use cryptonulz::modes::ctr::CounterOverflow;
+    use cryptonulz::modes::{Cbc, CounterSize, Ctr};
+    use cryptonulz::Error;
+
+    const IV: [u8; 16] = [0x11; 16];
+
+    fn aes() -> Aes128 {
+        Aes128::new(&hex_array(SP800_38A_KEY_128))
+    }
+
+    /// Encrypts `plaintext` block by block without adding any padding.
+    fn encrypt_raw(plaintext: &[u8]) -> Vec<u8> {
+        let mut ciphertext = plaintext.to_vec();
+        for block in ciphertext.chunks_exact_mut(16) {
+            aes().encrypt_block(block.try_into().unwrap());
+        }
+        ciphertext
+    }
+
+    #[test]
+    fn test_try_round_trip() {
+        for len in 0..=48 {
+            let plaintext: Vec<u8> = (0..len as u8).collect();
+            let mut buffer = plaintext.clone();
+            aes().try_encrypt(&mut buffer).unwrap();
+            assert_eq!(buffer.len(), (len / 16 + 1) * 16);
+            aes().try_decrypt(&mut buffer).unwrap();
+            assert_eq!(buffer, plaintext);
+        }
+    }
+
+    #[test]
+    fn test_try_decrypt_not_block_aligned() {
+        for len in [1, 15, 17, 31] {
+            let mut buffer = vec![0; len];
+            assert_eq!(aes().try_decrypt(&mut buffer), Err(Error::NotBlockAligned));
+            assert_eq!(buffer, vec![0; len]);
+        }
+    }
+
+    #[test]
+    fn test_try_decrypt_empty_ciphertext() {
+        // PKCS#7 always adds at least one byte.
+        assert_eq!(
+            aes().try_decrypt(&mut Vec::new()),
+            Err(Error::InvalidPadding)
+        );
+    }
+
+    #[test]
+    fn test_try_decrypt_padding_length_out_of_range() {
+        for last in [0x00, 0x11, 0x80, 0xff] {
+            let mut plaintext = [last; 32];
+            plaintext[..16].fill(0xaa);
+            let ciphertext = encrypt_raw(&plaintext);
+            let mut buffer = ciphertext.clone();
+            assert_eq!(
+                aes().try_decrypt(&mut buffer),
+                Err(Error::InvalidPadding),
+                "final byte {last:#04x}"
+            );
+            // The ciphertext is left as it was.
+            assert_eq!(buffer, ciphertext);
+        }
+    }
+
+    #[test]
+    fn test_try_decrypt_checks_every_padding_byte() {
+        for padding_len in 2..=16 {
+            for wrong in 16 - padding_len..15 {
+                let mut plaintext = [0x42; 16];
+                plaintext[16 - padding_len..].fill(padding_len as u8);
+                plaintext[wrong] ^= 0x01;
+                let mut buffer = encrypt_raw(&plaintext);
+                assert_eq!(
+                    aes().try_decrypt(&mut buffer),
+                    Err(Error::InvalidPadding),
+                    "padding length {padding_len}, wrong byte {wrong}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cbc_try_decrypt_errors() {
+        let mut cbc = Cbc::new(aes(), &IV);
+        let mut buffer = vec![0; 20];
+        assert_eq!(cbc.try_decrypt(&mut buffer), Err(Error::NotBlockAligned));
+
+        let mut ciphertext = b"sixteen bytes!!!".to_vec();
+        Cbc::new(aes(), &IV).encrypt(&mut ciphertext);
+        // Flip a bit of the padding via the first ciphertext block.
+        ciphertext[20] ^= 0x01;
+        let mut buffer = ciphertext.clone();
+        assert_eq!(cbc.try_decrypt(&mut buffer), Err(Error::InvalidPadding));
+        assert_eq!(buffer, ciphertext);
+        // The chaining value is untouched, so the instance is still usable.
+        assert_eq!(cbc.iv(), &IV);
+        ciphertext[20] ^= 0x01;
+        cbc.try_decrypt(&mut ciphertext).unwrap();
+        assert_eq!(ciphertext, b"sixteen bytes!!!");
+    }
+
+    #[test]
+    #[should_panic(expected = "decryption failed: invalid padding")]
+    fn test_decrypt_panics_on_invalid_padding() {
+        let mut buffer = encrypt_raw(&[0; 16]);
+        aes().decrypt(&mut buffer);
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            Error::NotBlockAligned.to_string(),
+            "data is not a multiple of the block size"
+        );
+        assert_eq!(Error::InvalidPadding.to_string(), "invalid padding");
+    }
+
+    /// Opens a GCM message, checks a CMAC tag, unwraps a key and runs a
+    /// counter, all through `?`.
+    fn open(
+        sealed: &mut Vec<u8>,
+        tag: &[u8],
+        wrapped: &[u8],
+        counter: &[u8; 16],
+    ) -> Result<Vec<u8>, Error> {
+        Gcm::new(aes()).decrypt(&[0; 12], &[], sealed)?;
+        let mut cmac = Cmac::new(aes());
+        cmac.update(sealed);
+        cmac.verify(tag)?;
+        let mut key = KeyWrap::new(aes()).unwrap(wrapped)?;
+        Ctr::with_counter_size(aes(), counter, CounterSize::U32).apply_keystream(&mut key)?;
+        Ok(key)
+    }
+
+    #[test]
+    fn test_module_errors_convert_with_question_mark() {
+        let mut sealed = b"message".to_vec();
+        Gcm::new(aes()).encrypt(&[0; 12], &[], &mut sealed);
+        let mut cmac = Cmac::new(aes());
+        cmac.update(b"message");
+        let tag = cmac.finalize();
+        let wrapped = KeyWrap::new(aes()).wrap(&[0x42; 32]);
+        let counter = [0; 16];
+        let last_counter = hex_array("000000000000000000000000ffffffff");
+
+        let mut key = [0x42; 32];
+        Ctr::new(aes(), &counter).apply_keystream(&mut key).unwrap();
+        assert_eq!(
+            open(&mut sealed.clone(), &tag, &wrapped, &counter),
+            Ok(key.to_vec())
+        );
+
+        let mut forged = sealed.clone();
+        forged[0] ^= 1;
+        assert_eq!(
+            open(&mut forged, &tag, &wrapped, &counter),
+            Err(Error::AuthenticationFailed)
+        );
+        assert_eq!(
+            open(&mut sealed.clone(), &[0; 16], &wrapped, &counter),
+            Err(Error::MacVerificationFailed)
+        );
+        assert_eq!(
+            open(&mut sealed.clone(), &tag, &wrapped[1..], &counter),
+            Err(Error::Unwrap(UnwrapError::InvalidLength))
+        );
+        assert_eq!(
+            open(&mut sealed.clone(), &tag, &[0; 40], &counter),
+            Err(Error::Unwrap(UnwrapError::IntegrityCheckFailed))
+        );
+        assert_eq!(
+            open(&mut sealed.clone(), &tag, &wrapped, &last_counter),
+            Err(Error::CounterOverflow)
+        );
+    }
+
+    #[test]
+    fn test_module_error_display() {
+        assert_eq!(
+            Error::from(aead::Error).to_string(),
+            aead::Error.to_string()
+        );
+        assert_eq!(Error::from(mac::Error).to_string(), mac::Error.to_string());
+        assert_eq!(
+            Error::from(UnwrapError::IntegrityCheckFailed).to_string(),
+            UnwrapError::IntegrityCheckFailed.to_string()
+        );
+        assert_eq!(
+            Error::from(CounterOverflow).to_string(),
+            CounterOverflow.to_string()
+        );
+    }
+}
@@ -0,0 +1,140 @@
+mod common;
+
+#[cfg(test)]
+mod gcm_siv_tests {
+    use crate::common::*;
+    use cryptonulz::aead::{Error, GcmSiv};
+    use cryptonulz::aes::*;
+
+    // RFC 8452, Appendix C.1 and C.2
+    const NONCE: &str = "030000000000000000000000";
+    const VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("0100000000000000", ""),
+        ("010000000000000000000000", ""),
+        ("01000000000000000000000000000000", ""),
+        (
+            "01000000000000000000000000000000 02000000000000000000000000000000",
+            "",
+        ),
+        ("0200000000000000", "01"),
+        (
+            "02000000000000000000000000000000 03000000000000000000000000000000
+             04000000000000000000000000000000 05000000000000000000000000000000
+             06000000000000000000000000000000",
+            "01",
+        ),
+    ];
+
+    fn check<C: Cryptoprovider>(
+        siv: &GcmSiv<C>,
+        nonce: &str,
+        plaintext: &str,
+        aad: &str,
+        result: &str,
+    ) {
+        let nonce = hex_array(nonce);
+        let mut data = hex(plaintext);
+        siv.encrypt(&nonce, &hex(aad), &mut data);
+        assert_eq!(data, hex(result));
+        siv.decrypt(&nonce, &hex(aad), &mut data).unwrap();
+        assert_eq!(data, hex(plaintext));
+    }
+
+    #[test]
+    fn test_aes128_gcm_siv_rfc8452() {
+        let siv = GcmSiv::<Aes128>::new(&hex_array("01000000000000000000000000000000"));
+        let results = [
+            "dc20e2d83f25705bb49e439eca56de25",
+            "b5d839330ac7b786578782fff6013b81 5b287c22493a364c",
+            "7323ea61d05932260047d942a4978db3 57391a0bc4fdec8b0d106639",
+            "743f7c8077ab25f8624e2e948579cf77 303aaf90f6fe21199c6068577437a0c4",
+            "84e07e62ba83a6585417245d7ec413a9 fe427d6315c09b57ce45f2e3936a9445
+             1a8e45dcd4578c667cd86847bf6155ff",
+            "1e6daba35669f4273b0a1a2560969cdf 790d99759abd1508",
+            "61ff6dacc5e0d0459e2cd3983ec0d478 9c5fabfa3a054caa5a19c07da71d17bf
+             8d9496207b6f8465cd14a9ed3a3f8746 86737ec4187ca4f44e7cff1c8ea11a96
+             4e6594ed6c5d9e8189a9614719a8923c ee8afa9c09565addf4ef6a7c8f231aa1",
+        ];
+        for ((plaintext, aad), result) in VECTORS.iter().zip(results) {
+            check(&siv, NONCE, plaintext, aad, result);
+        }
+    }
+
+    #[test]
+    fn test_aes256_gcm_siv_rfc8452() {
+        let siv = GcmSiv::<Aes256>::new(&hex_array(
+            "01000000000000000000000000000000 00000000000000000000000000000000",
+        ));
+        let results = [
+            "07f5f4169bbf55a8400cd47ea6fd400f",
+            "c2ef328e5c71c83b843122130f7364b7 61e0b97427e3df28",
+            "9aab2aeb3faa0a34aea8e2b18ca50da9 ae6559e48fd10f6e5c9ca17e",
+            "85a01b63025ba19b7fd3ddfc033b3e76 c9eac6fa700942702e90862383c6c366",
+            "4a6a9db4c8c6549201b9edb53006cba8 21ec9cf850948a7c86c68ac7539d027f
+             e819e63abcd020b006a976397632eb5d",
+            "1de22967237a813291213f267e3b452f 02d01ae33e4ec854",
+            "21ebf38a4bd7319aef17afe99119d9f2 1d2605feda4e0d93581c6912e3feb493
+             2e5f78d56b515131ec93902894537aee fa540b083ec04634b2d0b431aa9ab58c
+             d737a5549eec07ae2e22ae5350015971 484eb3d6569f42ce47f6ad6f5fb935a3",
+        ];
+        for ((plaintext, aad), result) in VECTORS.iter().zip(results) {
+            check(&siv, NONCE, plaintext, aad, result);
+        }
+    }
+
+    #[test]
+    fn test_aes256_gcm_siv_counter_wrap() {
+        // RFC 8452, Appendix C.3
+        let siv = GcmSiv::<Aes256>::new(&[0; 32]);
+        check(
+            &siv,
+            "000000000000000000000000",
+            "00000000000000000000000000000000 4db923dc793ee6497c76dcc03a98e108",
+            "",
+            "f3f80f2cf0cb2dd9c5984fcda908456c c537703b5ba70324a6793a7bf218d3ea
+             ffffffff000000000000000000000000",
+        );
+        check(
+            &siv,
+            "000000000000000000000000",
+            "eb3640277c7ffd1303c7a542d02d3e4c 0000000000000000",
+            "",
+            "18ce4f0b8cb4d0cac65fea8f79257b20 888e53e72299e56d
+             ffffffff000000000000000000000000",
+        );
+    }
+
+    #[test]
+    fn test_gcm_siv_rejects_tampering() {
+        let siv = GcmSiv::<Aes128>::new(&[3; 16]);
+        let nonce = [9; 12];
+        let mut sealed = b"nonce reuse is not fatal here".to_vec();
+        siv.encrypt(&nonce, b"aad", &mut sealed);
+
+        let mut flipped = sealed.clone();
+        flipped[1] ^= 0x10;
+        let expected = flipped.clone();
+        assert_eq!(siv.decrypt(&nonce, b"aad", &mut flipped), Err(Error));
+        assert_eq!(flipped, expected);
+
+        let mut other_nonce = sealed.clone();
+        assert_eq!(siv.decrypt(&[8; 12], b"aad", &mut other_nonce), Err(Error));
+
+        let mut short = sealed[..15].to_vec();
+        assert_eq!(siv.decrypt(&nonce, b"aad", &mut short), Err(Error));
+    }
+
+    #[test]
+    fn test_gcm_siv_nonce_reuse_is_deterministic() {
+        let siv = GcmSiv::<Aes256>::new(&[5; 32]);
+        let mut a = b"same message".to_vec();
+        let mut b = b"same message".to_vec();
+        let mut c = b"other message".to_vec();
+        siv.encrypt(&[1; 12], b"", &mut a);
+        siv.encrypt(&[1; 12], b"", &mut b);
+        siv.encrypt(&[1; 12], b"", &mut c);
+        assert_eq!(a, b);
+        assert_ne!(a[..12], c[..12]);
+    }
+}
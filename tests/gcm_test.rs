@@ -0,0 +1,278 @@
+mod common;
+
+#[cfg(test)]
+mod gcm_tests {
+    use crate::common::*;
+    use cryptonulz::aead::{Error, Gcm};
+    use cryptonulz::aes::*;
+
+    // Test cases from McGrew and Viega, "The Galois/Counter Mode of Operation".
+    const K: &str = "feffe9928665731c6d6a8f9467308308";
+    const P: &str = "d9313225f88406e5a55909c5aff5269a 86a7a9531534f7da2e4c303d8a318a72
+                     1c3c0c95956809532fcf0e2449a6b525 b16aedf5aa0de657ba637b391aafd255";
+    const P_SHORT: &str = "d9313225f88406e5a55909c5aff5269a 86a7a9531534f7da2e4c303d8a318a72
+                           1c3c0c95956809532fcf0e2449a6b525 b16aedf5aa0de657ba637b39";
+    const A: &str = "feedfacedeadbeeffeedfacedeadbeef abaddad2";
+    const IV: &str = "cafebabefacedbaddecaf888";
+    const IV_SHORT: &str = "cafebabefacedbad";
+    const IV_LONG: &str = "9313225df88406e555909c5aff5269aa 6a7a9538534f7da1e4c303d2a318a728
+                           c3c0c95156809539fcf0e2429a6b5254 16aedbf5a0de6a57a637b39b";
+
+    fn check<C: Cryptoprovider>(cipher: C, iv: &str, plaintext: &str, aad: &str, c: &str, t: &str) {
+        let gcm = Gcm::new(cipher);
+        let (iv, aad) = (hex(iv), hex(aad));
+        let mut data = hex(plaintext);
+        let tag = gcm.encrypt_detached(&iv, &aad, &mut data);
+        assert_eq!(data, hex(c));
+        assert_eq!(tag, hex(t));
+        gcm.decrypt_detached(&iv, &aad, &mut data, &tag).unwrap();
+        assert_eq!(data, hex(plaintext));
+
+        let mut attached = hex(plaintext);
+        gcm.encrypt(&iv, &aad, &mut attached);
+        assert_eq!(attached, [hex(c), hex(t)].concat());
+        gcm.decrypt(&iv, &aad, &mut attached).unwrap();
+        assert_eq!(attached, hex(plaintext));
+    }
+
+    #[test]
+    fn test_gcm_aes128_test_cases() {
+        let zero = Aes128::new(&[0; 16]);
+        let aes = Aes128::new(&hex_array(K));
+        check(
+            &zero,
+            "000000000000000000000000",
+            "",
+            "",
+            "",
+            "58e2fccefa7e3061367f1d57a4e7455a",
+        );
+        check(
+            &zero,
+            "000000000000000000000000",
+            "00000000000000000000000000000000",
+            "",
+            "0388dace60b6a392f328c2b971b2fe78",
+            "ab6e47d42cec13bdf53a67b21257bddf",
+        );
+        check(
+            &aes,
+            IV,
+            P,
+            "",
+            "42831ec2217774244b7221b784d0d49c e3aa212f2c02a4e035c17e2329aca12e
+             21d514b25466931c7d8f6a5aac84aa05 1ba30b396a0aac973d58e091473f5985",
+            "4d5c2af327cd64a62cf35abd2ba6fab4",
+        );
+        check(
+            &aes,
+            IV,
+            P_SHORT,
+            A,
+            "42831ec2217774244b7221b784d0d49c e3aa212f2c02a4e035c17e2329aca12e
+             21d514b25466931c7d8f6a5aac84aa05 1ba30b396a0aac973d58e091",
+            "5bc94fbc3221a5db94fae95ae7121a47",
+        );
+        check(
+            &aes,
+            IV_SHORT,
+            P_SHORT,
+            A,
+            "61353b4c2806934a777ff51fa22a4755 699b2a714fcdc6f83766e5f97b6c7423
+             73806900e49f24b22b097544d4896b42 4989b5e1ebac0f07c23f4598",
+            "3612d2e79e3b0785561be14aaca2fccb",
+        );
+        check(
+            &aes,
+            IV_LONG,
+            P_SHORT,
+            A,
+            "8ce24998625615b603a033aca13fb894 be9112a5c3a211a8ba262a3cca7e2ca7
+             01e4a9a4fba43c90ccdcb281d48c7c6f d62875d2aca417034c34aee5",
+            "619cc5aefffe0bfa462af43c1699d050",
+        );
+    }
+
+    #[test]
+    fn test_gcm_aes192_test_cases() {
+        let zero = Aes192::new(&[0; 24]);
+        let aes = Aes192::new(&hex_array(&[K, &K[..16]].concat()));
+        check(
+            &zero,
+            "000000000000000000000000",
+            "",
+            "",
+            "",
+            "cd33b28ac773f74ba00ed1f312572435",
+        );
+        check(
+            &zero,
+            "000000000000000000000000",
+            "00000000000000000000000000000000",
+            "",
+            "98e7247c07f0fe411c267e4384b0f600",
+            "2ff58d80033927ab8ef4d4587514f0fb",
+        );
+        check(
+            &aes,
+            IV,
+            P,
+            "",
+            "3980ca0b3c00e841eb06fac4872a2757 859e1ceaa6efd984628593b40ca1e19c
+             7d773d00c144c525ac619d18c84a3f47 18e2448b2fe324d9ccda2710acade256",
+            "9924a7c8587336bfb118024db8674a14",
+        );
+        check(
+            &aes,
+            IV,
+            P_SHORT,
+            A,
+            "3980ca0b3c00e841eb06fac4872a2757 859e1ceaa6efd984628593b40ca1e19c
+             7d773d00c144c525ac619d18c84a3f47 18e2448b2fe324d9ccda2710",
+            "2519498e80f1478f37ba55bd6d27618c",
+        );
+        check(
+            &aes,
+            IV_SHORT,
+            P_SHORT,
+            A,
+            "0f10f599ae14a154ed24b36e25324db8 c566632ef2bbb34f8347280fc4507057
+             fddc29df9a471f75c66541d4d4dad1c9 e93a19a58e8b473fa0f062f7",
+            "65dcc57fcf623a24094fcca40d3533f8",
+        );
+        check(
+            &aes,
+            IV_LONG,
+            P_SHORT,
+            A,
+            "d27e88681ce3243c4830165a8fdcf9ff 1de9a1d8e6b447ef6ef7b79828666e45
+             81e79012af34ddd9e2f037589b292db3 e67c036745fa22e7e9b7373b",
+            "dcf566ff291c25bbb8568fc3d376a6d9",
+        );
+    }
+
+    #[test]
+    fn test_gcm_aes256_test_cases() {
+        let zero = Aes256::new(&[0; 32]);
+        let aes = Aes256::new(&hex_array(&[K, K].concat()));
+        check(
+            &zero,
+            "000000000000000000000000",
+            "",
+            "",
+            "",
+            "530f8afbc74536b9a963b4f1c4cb738b",
+        );
+        check(
+            &zero,
+            "000000000000000000000000",
+            "00000000000000000000000000000000",
+            "",
+            "cea7403d4d606b6e074ec5d3baf39d18",
+            "d0d1c8a799996bf0265b98b5d48ab919",
+        );
+        check(
+            &aes,
+            IV,
+            P,
+            "",
+            "522dc1f099567d07f47f37a32a84427d 643a8cdcbfe5c0c97598a2bd2555d1aa
+             8cb08e48590dbb3da7b08b1056828838 c5f61e6393ba7a0abcc9f662898015ad",
+            "b094dac5d93471bdec1a502270e3cc6c",
+        );
+        check(
+            &aes,
+            IV,
+            P_SHORT,
+            A,
+            "522dc1f099567d07f47f37a32a84427d 643a8cdcbfe5c0c97598a2bd2555d1aa
+             8cb08e48590dbb3da7b08b1056828838 c5f61e6393ba7a0abcc9f662",
+            "76fc6ece0f4e1768cddf8853bb2d551b",
+        );
+        check(
+            &aes,
+            IV_SHORT,
+            P_SHORT,
+            A,
+            "c3762df1ca787d32ae47c13bf19844cb af1ae14d0b976afac52ff7d79bba9de0
+             feb582d33934a4f0954cc2363bc73f78 62ac430e64abe499f47c9b1f",
+            "3a337dbf46a792c45e454913fe2ea8f2",
+        );
+        check(
+            &aes,
+            IV_LONG,
+            P_SHORT,
+            A,
+            "5a8def2f0c9e53f1f75d7853659e2a20 eeb2b22aafde6419a058ab4f6f746bf4
+             0fc0c3b780f244452da3ebf1c5d82cde a2418997200ef82e44ae7e3f",
+            "a44a8266ee1c8eb0c8b5d4cf5ae9f19a",
+        );
+    }
+
+    #[test]
+    fn test_gcm_truncated_tag() {
+        let aes = Aes128::new(&hex_array(K));
+        let gcm = Gcm::with_tag_len(&aes, 12);
+        let mut data = hex(P_SHORT);
+        let tag = gcm.encrypt_detached(&hex(IV), &hex(A), &mut data);
+        assert_eq!(tag, hex("5bc94fbc3221a5db94fae95a"));
+
+        let mut attached = hex(P_SHORT);
+        gcm.encrypt(&hex(IV), &hex(A), &mut attached);
+        assert_eq!(attached.len(), 60 + 12);
+        gcm.decrypt(&hex(IV), &hex(A), &mut attached).unwrap();
+        assert_eq!(attached, hex(P_SHORT));
+    }
+
+    #[test]
+    fn test_gcm_rejects_tampering() {
+        let aes = Aes128::new(&hex_array(K));
+        let gcm = Gcm::new(&aes);
+        let mut sealed = hex(P_SHORT);
+        gcm.encrypt(&hex(IV), &hex(A), &mut sealed);
+
+        let mut flipped = sealed.clone();
+        flipped[3] ^= 1;
+        assert_eq!(gcm.decrypt(&hex(IV), &hex(A), &mut flipped), Err(Error));
+        assert_eq!(flipped[3], sealed[3] ^ 1);
+
+        let mut bad_tag = sealed.clone();
+        *bad_tag.last_mut().unwrap() ^= 0x80;
+        assert_eq!(gcm.decrypt(&hex(IV), &hex(A), &mut bad_tag), Err(Error));
+
+        let mut wrong_aad = sealed.clone();
+        assert_eq!(gcm.decrypt(&hex(IV), b"", &mut wrong_aad), Err(Error));
+
+        let mut too_short = sealed[..15].to_vec();
+        assert_eq!(gcm.decrypt(&hex(IV), &hex(A), &mut too_short), Err(Error));
+
+        let mut data = hex(P_SHORT);
+        let tag = gcm.encrypt_detached(&hex(IV), &hex(A), &mut data);
+        assert_eq!(
+            gcm.decrypt_detached(&hex(IV), &hex(A), &mut data, &tag[..12]),
+            Err(Error)
+        );
+    }
+
+    #[test]
+    fn test_gcm_decrypt_with_empty_nonce_fails() {
+        let gcm = Gcm::new(Aes128::new(&hex_array(K)));
+        let mut sealed = hex(P_SHORT);
+        gcm.encrypt(&hex(IV), &hex(A), &mut sealed);
+        let copy = sealed.clone();
+        assert_eq!(gcm.decrypt(&[], &hex(A), &mut sealed), Err(Error));
+        assert_eq!(sealed, copy);
+        let (ciphertext, tag) = sealed.split_at_mut(copy.len() - 16);
+        assert_eq!(
+            gcm.decrypt_detached(&[], &hex(A), ciphertext, tag),
+            Err(Error)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "GCM needs a non-empty nonce")]
+    fn test_gcm_encrypt_with_empty_nonce_panics() {
+        let gcm = Gcm::new(Aes128::new(&hex_array(K)));
+        gcm.encrypt(&[], &hex(A), &mut hex(P_SHORT));
+    }
+}
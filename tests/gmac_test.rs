@@ -0,0 +1,55 @@
+mod common;
+
+#[cfg(test)]
+mod gmac_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::mac::{Error, Gmac};
+
+    // IEEE 802.1AE-2006 Annex C, 2.1.1 and 2.1.2: 54-byte packet
+    // authentication with GCM-AES-128 and GCM-AES-256.
+    const NONCE: &str = "12153524c0895e81b2c28465";
+    const PACKET: &str = "d609b1f056637a0d46df998d88e5222a b2c2846512153524c0895e8108000f10
+                          1112131415161718191a1b1c1d1e1f20 2122232425262728292a2b2c2d2e2f30
+                          313233340001";
+
+    #[test]
+    fn test_gmac_aes128() {
+        let gmac = Gmac::new(Aes128::new(&hex_array("ad7a2bd03eac835a6f620fdcb506b345")));
+        let tag = hex("f09478a9b09007d06f46e9b6a1da25dd");
+        assert_eq!(gmac.mac(&hex(NONCE), &hex(PACKET)), tag);
+        assert_eq!(gmac.verify(&hex(NONCE), &hex(PACKET), &tag), Ok(()));
+    }
+
+    #[test]
+    fn test_gmac_aes256() {
+        let gmac = Gmac::new(Aes256::new(&hex_array(
+            "e3c08a8f06c6e3ad95a70557b23f75483ce33021a9c72b7025666204c69c0b72",
+        )));
+        let tag = hex("2f0bc5af409e06d609ea8b7d0fa5ea50");
+        assert_eq!(gmac.mac(&hex(NONCE), &hex(PACKET)), tag);
+        assert_eq!(gmac.verify(&hex(NONCE), &hex(PACKET), &tag), Ok(()));
+    }
+
+    #[test]
+    fn test_gmac_rejects_modifications() {
+        let gmac = Gmac::with_tag_len(
+            Aes128::new(&hex_array("ad7a2bd03eac835a6f620fdcb506b345")),
+            12,
+        );
+        assert_eq!(gmac.tag_len(), 12);
+        let nonce = hex(NONCE);
+        let packet = hex(PACKET);
+        let tag = gmac.mac(&nonce, &packet);
+        assert_eq!(tag, hex("f09478a9b09007d06f46e9b6"));
+
+        let mut modified = packet.clone();
+        modified[20] ^= 4;
+        assert_eq!(gmac.verify(&nonce, &modified, &tag), Err(Error));
+        let mut other_nonce = nonce.clone();
+        other_nonce[0] ^= 1;
+        assert_eq!(gmac.verify(&other_nonce, &packet, &tag), Err(Error));
+        assert_eq!(gmac.verify(&nonce, &packet, &tag[..11]), Err(Error));
+        assert_eq!(gmac.verify(&[], &packet, &tag), Err(Error));
+    }
+}
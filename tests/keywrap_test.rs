@@ -0,0 +1,136 @@
+mod common;
+
+#[cfg(test)]
+mod keywrap_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::keywrap::{KeyWrap, UnwrapError};
+
+    const KEK: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    const KEY: &str = "00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f";
+
+    fn check_kw<C: Cryptoprovider>(kw: KeyWrap<C>, key: &[u8], wrapped: &str) {
+        assert_eq!(kw.wrap(key), hex(wrapped));
+        assert_eq!(kw.unwrap(&hex(wrapped)).unwrap(), key);
+    }
+
+    #[test]
+    fn test_kw_rfc3394_vectors() {
+        let kek = hex(KEK);
+        let key = hex(KEY);
+        let aes128 = || Aes128::new(kek[..16].try_into().unwrap());
+        let aes192 = || Aes192::new(kek[..24].try_into().unwrap());
+        let aes256 = || Aes256::new(kek[..32].try_into().unwrap());
+
+        // RFC 3394, sections 4.1 to 4.6.
+        check_kw(
+            KeyWrap::new(aes128()),
+            &key[..16],
+            "1fa68b0a8112b447 aef34bd8fb5a7b82 9d3e862371d2cfe5",
+        );
+        check_kw(
+            KeyWrap::new(aes192()),
+            &key[..16],
+            "96778b25ae6ca435 f92b5b97c050aed2 468ab8a17ad84e5d",
+        );
+        check_kw(
+            KeyWrap::new(aes256()),
+            &key[..16],
+            "64e8c3f9ce0f5ba2 63e9777905818a2a 93c8191e7d6e8ae7",
+        );
+        check_kw(
+            KeyWrap::new(aes192()),
+            &key[..24],
+            "031d33264e15d332 68f24ec260743edc e1c6c7ddee725a93 6ba814915c6762d2",
+        );
+        check_kw(
+            KeyWrap::new(aes256()),
+            &key[..24],
+            "a8f9bc1612c68b3f f6e6f4fbe30e71e4 769c8b80a32cb895 8cd5d17d6b254da1",
+        );
+        check_kw(
+            KeyWrap::new(aes256()),
+            &key,
+            "28c9f404c4b810f4 cbccb35cfb87f826 3f5786e2d80ed326 cbc7f0e71a99f43b fb988b9b7a02dd21",
+        );
+    }
+
+    #[test]
+    fn test_kwp_rfc5649_vectors() {
+        let kw = KeyWrap::new(Aes192::new(&hex_array(
+            "5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8",
+        )));
+
+        let key = hex("c37b7e6492584340 bed1220780894115 5068f738");
+        let wrapped = hex("138bdeaa9b8fa7fc 61f97742e72248ee 5ae6ae5360d1ae6a 5f54f373fa543b6a");
+        assert_eq!(kw.wrap_padded(&key), wrapped);
+        assert_eq!(kw.unwrap_padded(&wrapped).unwrap(), key);
+
+        let key = hex("466f7250617369");
+        let wrapped = hex("afbeb0f07dfbf541 9200f2ccb50bb24f");
+        assert_eq!(kw.wrap_padded(&key), wrapped);
+        assert_eq!(kw.unwrap_padded(&wrapped).unwrap(), key);
+    }
+
+    #[test]
+    fn test_kwp_round_trip_all_lengths() {
+        let kw = KeyWrap::new(Aes128::new(&[0x5a; 16]));
+        for len in 1..=40usize {
+            let key: Vec<u8> = (0..len as u8).collect();
+            let wrapped = kw.wrap_padded(&key);
+            assert_eq!(wrapped.len(), len.next_multiple_of(8) + 8);
+            assert_eq!(kw.unwrap_padded(&wrapped).unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn test_unwrap_rejects_tampering() {
+        let kw = KeyWrap::new(Aes256::new(&hex_array(KEK)));
+        let key = hex(KEY);
+        let wrapped = kw.wrap(&key);
+        for i in 0..wrapped.len() {
+            let mut tampered = wrapped.clone();
+            tampered[i] ^= 0x80;
+            assert_eq!(kw.unwrap(&tampered), Err(UnwrapError::IntegrityCheckFailed));
+        }
+
+        let other = KeyWrap::new(Aes256::new(&[0; 32]));
+        assert_eq!(
+            other.unwrap(&wrapped),
+            Err(UnwrapError::IntegrityCheckFailed)
+        );
+
+        let wrapped = kw.wrap_padded(&key[..5]);
+        let mut tampered = wrapped.clone();
+        tampered[0] ^= 1;
+        assert_eq!(
+            kw.unwrap_padded(&tampered),
+            Err(UnwrapError::IntegrityCheckFailed)
+        );
+        // A KW output never passes the KWP integrity check.
+        assert_eq!(
+            kw.unwrap_padded(&kw.wrap(&key)),
+            Err(UnwrapError::IntegrityCheckFailed)
+        );
+    }
+
+    #[test]
+    fn test_unwrap_rejects_invalid_lengths() {
+        let kw = KeyWrap::new(Aes128::new(&[0; 16]));
+        for len in [0, 8, 16, 23, 25] {
+            assert_eq!(kw.unwrap(&vec![0; len]), Err(UnwrapError::InvalidLength));
+        }
+        for len in [0, 8, 15, 17] {
+            assert_eq!(
+                kw.unwrap_padded(&vec![0; len]),
+                Err(UnwrapError::InvalidLength)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_kw_rejects_unaligned_key() {
+        KeyWrap::new(Aes128::new(&[0; 16])).wrap(&[0; 20]);
+    }
+}
@@ -0,0 +1,197 @@
+mod common;
+
+#[cfg(test)]
+mod ocb_tests {
+    use crate::common::*;
+    use cryptonulz::aead::{Error, Ocb};
+    use cryptonulz::aes::*;
+
+    fn counting(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn test_ocb_aes128_rfc7253_sample_results() {
+        // RFC 7253, Appendix A
+        let aes = Aes128::new(&hex_array("000102030405060708090a0b0c0d0e0f"));
+        let ocb = Ocb::new(&aes);
+        let samples = [
+            ("00", 0, 0, "785407bfffc8ad9edcc5520ac9111ee6"),
+            (
+                "01",
+                8,
+                8,
+                "6820b3657b6f615a5725bda0d3b4eb3a257c9af1f8f03009",
+            ),
+            ("02", 8, 0, "81017f8203f081277152fade694a0a00"),
+            (
+                "03",
+                0,
+                8,
+                "45dd69f8f5aae72414054cd1f35d82760b2cd00d2f99bfa9",
+            ),
+            (
+                "04",
+                16,
+                16,
+                "571d535b60b277188be5147170a9a22c 3ad7a4ff3835b8c5701c1ccec8fc3358",
+            ),
+            ("05", 16, 0, "8cf761b6902ef764462ad86498ca6b97"),
+            (
+                "06",
+                0,
+                16,
+                "5ce88ec2e0692706a915c00aeb8b2396 f40e1c743f52436bdf06d8fa1eca343d",
+            ),
+            (
+                "07",
+                24,
+                24,
+                "1ca2207308c87c010756104d8840ce19 52f09673a448a122c92c62241051f573
+                 56d7f3c90bb0e07f",
+            ),
+        ];
+        for (nonce, aad_len, len, sealed) in samples {
+            let nonce = hex(&format!("bbaa998877665544332211{nonce}"));
+            let aad = counting(aad_len);
+            let mut data = counting(len);
+            ocb.encrypt(&nonce, &aad, &mut data);
+            assert_eq!(data, hex(sealed));
+            ocb.decrypt(&nonce, &aad, &mut data).unwrap();
+            assert_eq!(data, counting(len));
+        }
+    }
+
+    #[test]
+    fn test_ocb_aes128_rfc7253_taglen96() {
+        let aes = Aes128::new(&hex_array("0f0e0d0c0b0a09080706050403020100"));
+        let ocb = Ocb::with_tag_len(&aes, 12);
+        let nonce = hex("bbaa9988776655443322110d");
+        let mut data = counting(40);
+        ocb.encrypt(&nonce, &counting(40), &mut data);
+        assert_eq!(
+            data,
+            hex(
+                "1792a4e31e0755fb03e31b22116e6c2d df9efd6e33d536f1a0124b0a55bae884
+                 ed93481529c76b6ad0c515f4d1cdd4fd ac4f02aa"
+            )
+        );
+        ocb.decrypt(&nonce, &counting(40), &mut data).unwrap();
+        assert_eq!(data, counting(40));
+    }
+
+    /// The iterated test of RFC 7253, Appendix A, which covers every
+    /// combination of AES key size and 128, 96 and 64 bit tags.
+    fn iterated<C: Cryptoprovider>(
+        new_cipher: impl Fn(&[u8]) -> C,
+        key_len: usize,
+        tag_len: usize,
+    ) -> Vec<u8> {
+        let mut key = vec![0; key_len];
+        key[key_len - 1] = (tag_len * 8) as u8;
+        let aes = new_cipher(&key);
+        let ocb = Ocb::with_tag_len(&aes, tag_len);
+        let nonce = |n: u64| [&[0u8; 4][..], &n.to_be_bytes()].concat();
+        let mut c = Vec::new();
+        for i in 0..128u64 {
+            let s = vec![0; i as usize];
+            let mut p = s.clone();
+            ocb.encrypt(&nonce(3 * i + 1), &s, &mut p);
+            c.extend(p);
+            let mut p = s.clone();
+            ocb.encrypt(&nonce(3 * i + 2), &[], &mut p);
+            c.extend(p);
+            let mut p = Vec::new();
+            ocb.encrypt(&nonce(3 * i + 3), &s, &mut p);
+            c.extend(p);
+        }
+        let mut output = Vec::new();
+        ocb.encrypt(&nonce(385), &c, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_ocb_rfc7253_iterated_all_key_sizes() {
+        let aes128 = |k: &[u8]| Aes128::new(k.try_into().unwrap());
+        let aes192 = |k: &[u8]| Aes192::new(k.try_into().unwrap());
+        let aes256 = |k: &[u8]| Aes256::new(k.try_into().unwrap());
+        assert_eq!(
+            iterated(aes128, 16, 16),
+            hex("67e944d23256c5e0b6c61fa22fdf1ea2")
+        );
+        assert_eq!(
+            iterated(aes192, 24, 16),
+            hex("f673f2c3e7174aae7bae986ca9f29e17")
+        );
+        assert_eq!(
+            iterated(aes256, 32, 16),
+            hex("d90eb8e9c977c88b79dd793d7ffa161c")
+        );
+        assert_eq!(iterated(aes128, 16, 12), hex("77a3d8e73589158d25d01209"));
+        assert_eq!(iterated(aes192, 24, 12), hex("05d56ead2752c86be6932c5e"));
+        assert_eq!(iterated(aes256, 32, 12), hex("5458359ac23b0cba9e6330dd"));
+        assert_eq!(iterated(aes128, 16, 8), hex("192c9b7bd90ba06a"));
+        assert_eq!(iterated(aes192, 24, 8), hex("0066bc6e0ef34e24"));
+        assert_eq!(iterated(aes256, 32, 8), hex("7d4ea5d445501cbe"));
+    }
+
+    #[test]
+    fn test_ocb_nonce_sizes() {
+        let aes = Aes128::new(&[1; 16]);
+        let ocb = Ocb::new(&aes);
+        let mut outputs = Vec::new();
+        for nonce_len in 1..16 {
+            let nonce = vec![0xab; nonce_len];
+            let mut data = counting(37);
+            ocb.encrypt(&nonce, b"header", &mut data);
+            ocb.decrypt(&nonce, b"header", &mut data.clone()).unwrap();
+            outputs.push(data);
+        }
+        outputs.dedup();
+        assert_eq!(outputs.len(), 15);
+    }
+
+    #[test]
+    fn test_ocb_rejects_tampering() {
+        let aes = Aes128::new(&hex_array("000102030405060708090a0b0c0d0e0f"));
+        let ocb = Ocb::new(&aes);
+        let nonce = hex("bbaa99887766554433221107");
+        let mut sealed = counting(24);
+        ocb.encrypt(&nonce, &counting(24), &mut sealed);
+
+        for i in [0, 20] {
+            let mut flipped = sealed.clone();
+            flipped[i] ^= 2;
+            let expected = flipped.clone();
+            assert_eq!(ocb.decrypt(&nonce, &counting(24), &mut flipped), Err(Error));
+            assert_eq!(flipped, expected);
+        }
+
+        let mut bad_aad = sealed.clone();
+        assert_eq!(ocb.decrypt(&nonce, &counting(23), &mut bad_aad), Err(Error));
+        let mut short = sealed[..15].to_vec();
+        assert_eq!(ocb.decrypt(&nonce, &[], &mut short), Err(Error));
+    }
+
+    #[test]
+    fn test_ocb_decrypt_rejects_bad_nonce_length() {
+        let ocb = Ocb::new(Aes128::new(&[0; 16]));
+        let mut sealed = counting(24);
+        ocb.encrypt(&[1; 12], b"", &mut sealed);
+        let copy = sealed.clone();
+        for nonce_len in [0, 16, 17] {
+            assert_eq!(
+                ocb.decrypt(&vec![1; nonce_len], b"", &mut sealed),
+                Err(Error)
+            );
+            assert_eq!(sealed, copy);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "OCB nonces are 1 to 15 bytes long")]
+    fn test_ocb_encrypt_with_empty_nonce_panics() {
+        let ocb = Ocb::new(Aes128::new(&[0; 16]));
+        ocb.encrypt(&[], b"", &mut counting(16));
+    }
+}
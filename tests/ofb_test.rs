@@ -0,0 +1,75 @@
+mod common;
+
+#[cfg(test)]
+mod ofb_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::{Ofb, OfbState};
+
+    const IV: &str = "000102030405060708090a0b0c0d0e0f";
+
+    fn check_sp800_38a<C: Cryptoprovider>(cipher: C, ciphertext: &str) {
+        let iv = hex_array(IV);
+        let mut data = hex(SP800_38A_PLAINTEXT);
+        Ofb::new(&cipher, &iv).apply_keystream(&mut data);
+        assert_eq!(data, hex(ciphertext));
+        Ofb::new(&cipher, &iv).apply_keystream(&mut data);
+        assert_eq!(data, hex(SP800_38A_PLAINTEXT));
+    }
+
+    #[test]
+    fn test_ofb_aes128_sp800_38a() {
+        check_sp800_38a(
+            Aes128::new(&hex_array(SP800_38A_KEY_128)),
+            "3b3fd92eb72dad20333449f8e83cfb4a 7789508d16918f03f53c52dac54ed825
+             9740051e9c5fecf64344f7a82260edcc 304c6528f659c77866a510d9c1d6ae5e",
+        );
+    }
+
+    #[test]
+    fn test_ofb_aes192_sp800_38a() {
+        check_sp800_38a(
+            Aes192::new(&hex_array(SP800_38A_KEY_192)),
+            "cdc80d6fddf18cab34c25909c99a4174 fcc28b8d4c63837c09e81700c1100401
+             8d9a9aeac0f6596f559c6d4daf59a5f2 6d9f200857ca6c3e9cac524bd9acc92a",
+        );
+    }
+
+    #[test]
+    fn test_ofb_aes256_sp800_38a() {
+        check_sp800_38a(
+            Aes256::new(&hex_array(SP800_38A_KEY_256)),
+            "dc7e84bfda79164b7ecd8486985d3860 4febdc6740d20b3ac88f6ad82a4fb08d
+             71ab47a086e86eedf39d1c5bba97c408 0126141d67f37be8538f5a8be740e484",
+        );
+    }
+
+    #[test]
+    fn test_ofb_resume_from_state() {
+        let aes = Aes128::new(&hex_array(SP800_38A_KEY_128));
+        let iv = hex_array(IV);
+        let mut whole = hex(SP800_38A_PLAINTEXT);
+        Ofb::new(&aes, &iv).apply_keystream(&mut whole);
+
+        for split in [0, 7, 16, 23, 64] {
+            let mut data = hex(SP800_38A_PLAINTEXT);
+            let (head, tail) = data.split_at_mut(split);
+            let saved = {
+                let mut ofb = Ofb::new(&aes, &iv);
+                ofb.apply_keystream(head);
+                ofb.state().to_bytes()
+            };
+
+            let state = OfbState::from_bytes(&saved).unwrap();
+            Ofb::from_state(&aes, state).apply_keystream(tail);
+            assert_eq!(data, whole);
+        }
+    }
+
+    #[test]
+    fn test_ofb_state_rejects_bad_position() {
+        let mut bytes = [0u8; OfbState::ENCODED_LEN];
+        bytes[BLOCKSIZE] = BLOCKSIZE as u8 + 1;
+        assert_eq!(OfbState::from_bytes(&bytes), None);
+    }
+}
@@ -0,0 +1,178 @@
+mod common;
+
+#[cfg(test)]
+mod padding_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::Cbc;
+    use cryptonulz::Error;
+    use PaddingStrategy::*;
+
+    const ALL: [PaddingStrategy; 6] = [PKCS7, ZERO, ISO7816, ANSIX923, ISO10126, NONE];
+
+    fn aes(padding: PaddingStrategy) -> Aes128 {
+        Aes128::with_padding(&hex_array(SP800_38A_KEY_128), padding)
+    }
+
+    /// The padded plaintext, recovered by decrypting without unpadding.
+    fn padded(padding: PaddingStrategy, data: &[u8]) -> Vec<u8> {
+        let mut buffer = data.to_vec();
+        aes(padding).try_encrypt(&mut buffer).unwrap();
+        aes(NONE).try_decrypt(&mut buffer).unwrap();
+        buffer
+    }
+
+    /// Encrypts a crafted final block and unpads it with `padding`.
+    fn unpad_block(padding: PaddingStrategy, block: [u8; 16]) -> Result<Vec<u8>, Error> {
+        let mut buffer = block.to_vec();
+        aes(NONE).try_encrypt(&mut buffer).unwrap();
+        aes(padding).try_decrypt(&mut buffer).map(|_| buffer)
+    }
+
+    #[test]
+    fn test_round_trip_all_schemes() {
+        for padding in ALL {
+            for len in 0..=48 {
+                // NONE only takes aligned data and ZERO loses trailing zeros.
+                if padding == NONE && len % 16 != 0 {
+                    continue;
+                }
+                let data: Vec<u8> = (1..=len as u8).collect();
+                let mut buffer = data.clone();
+                aes(padding).try_encrypt(&mut buffer).unwrap();
+                aes(padding).try_decrypt(&mut buffer).unwrap();
+                assert_eq!(buffer, data, "{padding:?}, {len} bytes");
+
+                let mut buffer = data.clone();
+                Cbc::with_padding(aes(PKCS7), &[7; 16], padding).encrypt(&mut buffer);
+                Cbc::with_padding(aes(PKCS7), &[7; 16], padding).decrypt(&mut buffer);
+                assert_eq!(buffer, data, "CBC {padding:?}, {len} bytes");
+            }
+        }
+    }
+
+    #[test]
+    fn test_padding_bytes() {
+        let data = b"0123456789a";
+        assert_eq!(padded(PKCS7, data), [&data[..], &[5; 5]].concat());
+        assert_eq!(padded(ZERO, data), [&data[..], &[0; 5]].concat());
+        assert_eq!(
+            padded(ISO7816, data),
+            [&data[..], &[0x80, 0, 0, 0, 0]].concat()
+        );
+        assert_eq!(
+            padded(ANSIX923, data),
+            [&data[..], &[0, 0, 0, 0, 5]].concat()
+        );
+        let iso10126 = padded(ISO10126, data);
+        assert_eq!(iso10126[..11], data[..]);
+        assert_eq!(iso10126[15], 5);
+
+        // Aligned data gets a full block, except for ZERO and NONE.
+        let data = [0x33; 16];
+        assert_eq!(padded(PKCS7, &data).len(), 32);
+        assert_eq!(
+            padded(ISO7816, &data)[16..],
+            hex("80000000000000000000000000000000")
+        );
+        assert_eq!(
+            padded(ANSIX923, &data)[16..],
+            hex("00000000000000000000000000000010")
+        );
+        assert_eq!(padded(ISO10126, &data).len(), 32);
+        assert_eq!(padded(ZERO, &data), data);
+        assert_eq!(padded(NONE, &data), data);
+    }
+
+    #[test]
+    fn test_iso10126_fills_randomly() {
+        let a = padded(ISO10126, b"x");
+        let b = padded(ISO10126, b"x");
+        assert_eq!(a[15], 15);
+        assert_eq!(b[15], 15);
+        // 14 random bytes collide with probability 2^-112.
+        assert_ne!(a[1..15], b[1..15]);
+    }
+
+    #[test]
+    fn test_zero_strips_trailing_zeros() {
+        assert_eq!(padded(ZERO, b""), b"");
+        let mut buffer = b"abc\0\0".to_vec();
+        aes(ZERO).try_encrypt(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), 16);
+        aes(ZERO).try_decrypt(&mut buffer).unwrap();
+        assert_eq!(buffer, b"abc");
+    }
+
+    #[test]
+    fn test_none_rejects_unaligned_input() {
+        for len in [1, 15, 17] {
+            let mut buffer = vec![0xab; len];
+            assert_eq!(
+                aes(NONE).try_encrypt(&mut buffer),
+                Err(Error::NotBlockAligned)
+            );
+            assert_eq!(buffer, vec![0xab; len]);
+        }
+        let mut buffer = vec![0xab; 20];
+        let mut cbc = Cbc::with_padding(aes(PKCS7), &[0; 16], NONE);
+        assert_eq!(cbc.try_encrypt(&mut buffer), Err(Error::NotBlockAligned));
+    }
+
+    #[test]
+    fn test_malformed_iso7816() {
+        let mut block = [0; 16];
+        block[..12].fill(0x41);
+        block[12] = 0x80;
+        assert_eq!(unpad_block(ISO7816, block).unwrap().len(), 12);
+        // Non-zero bytes after the marker.
+        block[14] = 1;
+        assert_eq!(unpad_block(ISO7816, block), Err(Error::InvalidPadding));
+        // No marker at all.
+        assert_eq!(unpad_block(ISO7816, [0; 16]), Err(Error::InvalidPadding));
+        assert_eq!(unpad_block(ISO7816, [0x41; 16]), Err(Error::InvalidPadding));
+        // A marker of the wrong value.
+        let mut block = [0x41; 16];
+        block[15] = 0x81;
+        assert_eq!(unpad_block(ISO7816, block), Err(Error::InvalidPadding));
+    }
+
+    #[test]
+    fn test_malformed_ansix923() {
+        let mut block = [0x41; 16];
+        block[10..].fill(0);
+        block[15] = 6;
+        assert_eq!(unpad_block(ANSIX923, block).unwrap().len(), 10);
+        for wrong in 10..15 {
+            let mut block = block;
+            block[wrong] = 6;
+            assert_eq!(unpad_block(ANSIX923, block), Err(Error::InvalidPadding));
+        }
+        for length in [0, 17, 0xff] {
+            let mut block = [0; 16];
+            block[15] = length;
+            assert_eq!(unpad_block(ANSIX923, block), Err(Error::InvalidPadding));
+        }
+    }
+
+    #[test]
+    fn test_malformed_iso10126() {
+        // Only the length byte carries structure.
+        let mut block = [0x5a; 16];
+        block[15] = 16;
+        assert_eq!(unpad_block(ISO10126, block).unwrap(), b"");
+        for length in [0, 17, 0xff] {
+            block[15] = length;
+            assert_eq!(unpad_block(ISO10126, block), Err(Error::InvalidPadding));
+        }
+    }
+
+    #[test]
+    fn test_malformed_pkcs7() {
+        let mut block = [4; 16];
+        block[..12].fill(0x41);
+        assert_eq!(unpad_block(PKCS7, block).unwrap().len(), 12);
+        block[12] = 3;
+        assert_eq!(unpad_block(PKCS7, block), Err(Error::InvalidPadding));
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+#[cfg(test)]
+mod pmac_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::mac::{Error, Pmac};
+
+    // Published PMAC1 test vectors: key 00 01 .. 0f (or .. 1f) and messages
+    // of counting bytes, except for the last one, which is 1000 zero bytes.
+    const AES128_TAGS: [(usize, &str); 6] = [
+        (0, "4399572cd6ea5341b8d35876a7098af7"),
+        (3, "256ba5193c1b991b4df0c51f388a9e27"),
+        (16, "ebbd822fa458daf6dfdad7c27da76338"),
+        (20, "0412ca150bbf79058d8c75a58c993f55"),
+        (32, "e97ac04e9e5e3399ce5355cd7407bc75"),
+        (34, "5cba7d5eb24f7c86ccc54604e53d5512"),
+    ];
+    const AES128_TAG_1000_ZEROS: &str = "c2c9fa1d9985f6f0d2aff915a0e8d910";
+    const AES256_TAGS: [(usize, &str); 6] = [
+        (0, "e620f52fe75bbe87ab758c0624943d8b"),
+        (3, "ffe124cc152cfb2bf1ef5409333c1c9a"),
+        (16, "853fdbf3f91dcd36380d698a64770bab"),
+        (20, "7711395fbe9dec19861aeb96e052cd1b"),
+        (32, "08fa25c28678c84d383130653e77f4c0"),
+        (34, "edd8a05f4b66761f9eee4feb4ed0c3a1"),
+    ];
+    const AES256_TAG_1000_ZEROS: &str = "69aa77f231eb0cdff960f5561d29a96e";
+
+    fn check<C: Cryptoprovider>(mut pmac: Pmac<C>, tags: [(usize, &str); 6], zeros: &str) {
+        for (len, tag) in tags {
+            let message: Vec<u8> = (0..len as u8).collect();
+            pmac.update(&message);
+            assert_eq!(pmac.finalize(), hex(tag));
+            pmac.update(&message);
+            assert_eq!(pmac.verify(&hex(tag)), Ok(()));
+        }
+        pmac.update(&[0; 1000]);
+        assert_eq!(pmac.finalize(), hex(zeros));
+    }
+
+    #[test]
+    fn test_pmac_aes128_vectors() {
+        let key: Vec<u8> = (0..16).collect();
+        let pmac = Pmac::new(Aes128::new(key[..].try_into().unwrap()));
+        check(pmac, AES128_TAGS, AES128_TAG_1000_ZEROS);
+    }
+
+    #[test]
+    fn test_pmac_aes256_vectors() {
+        let key: Vec<u8> = (0..32).collect();
+        let pmac = Pmac::new(Aes256::new(key[..].try_into().unwrap()));
+        check(pmac, AES256_TAGS, AES256_TAG_1000_ZEROS);
+    }
+
+    #[test]
+    fn test_pmac_incremental_update() {
+        let mut pmac = Pmac::new(Aes128::new(&[7; 16]));
+        let message: Vec<u8> = (0..200u8).collect();
+        pmac.update(&message);
+        let expected = pmac.finalize();
+        for split in 0..=message.len() {
+            pmac.update(&message[..split]);
+            pmac.update(&message[split..]);
+            assert_eq!(pmac.finalize(), expected);
+        }
+        pmac.update(b"discarded");
+        pmac.reset();
+        pmac.update(&message);
+        assert_eq!(pmac.finalize(), expected);
+    }
+
+    #[test]
+    fn test_pmac_parallel_matches_serial() {
+        let mut pmac = Pmac::new(Aes128::new(&[9; 16]));
+        let message: Vec<u8> = (0..100_003u32).map(|i| (i * 31) as u8).collect();
+        for len in [0, 1, 15, 16, 17, 64 * 1024, 64 * 1024 + 5, message.len()] {
+            pmac.update(&message[..len]);
+            let expected = pmac.finalize();
+            for threads in [1, 2, 3, 8] {
+                pmac.update_parallel(&message[..len], threads);
+                assert_eq!(pmac.finalize(), expected, "len {len}, {threads} threads");
+            }
+            // Mixed with serial updates on either side of the parallel one.
+            if len > 20 {
+                pmac.update(&message[..5]);
+                pmac.update_parallel(&message[5..len - 3], 4);
+                pmac.update(&message[len - 3..len]);
+                assert_eq!(pmac.finalize(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pmac_truncated_tag_and_wrong_tag() {
+        let key: Vec<u8> = (0..16).collect();
+        let mut pmac = Pmac::with_tag_len(Aes128::new(key[..].try_into().unwrap()), 10);
+        let tag = hex(AES128_TAGS[5].1);
+        let message: Vec<u8> = (0..34).collect();
+        pmac.update(&message);
+        assert_eq!(pmac.finalize(), tag[..10]);
+        pmac.update(&message);
+        assert_eq!(pmac.verify(&tag), Err(Error));
+        let mut wrong = tag[..10].to_vec();
+        wrong[9] ^= 1;
+        pmac.update(&message);
+        assert_eq!(pmac.verify(&wrong), Err(Error));
+    }
+}
@@ -0,0 +1,132 @@
+mod common;
+
+#[cfg(test)]
+mod poly1305_tests {
+    use crate::common::*;
+    use cryptonulz::mac::{Error, Poly1305, Poly1305Aes};
+
+    // Bernstein, "The Poly1305-AES message-authentication code", appendix B,
+    // as (message, k || r, nonce, tag).
+    const PAPER_VECTORS: [(&str, &str, &str, &str); 4] = [
+        (
+            "f3f6",
+            "ec074c835580741701425b623235add6 851fc40c3467ac0be05cc20404f3f700",
+            "fb447350c4e868c52ac3275cf9d4327e",
+            "f4c633c3044fc145f84f335cb81953de",
+        ),
+        (
+            "",
+            "75deaa25c09f208e1dc4ce6b5cad3fbf a0f30800 00f46400d0c7e9076c834403",
+            "61ee09218d29b0aaed7e154a2c5509cc",
+            "dd3fab2251f11ac759f0887129cc2ee7",
+        ),
+        (
+            "663cea190ffb83d89593f3f476b6bc24 d7e679107ea26adb8caf6652d0656136",
+            "6acb5f61a7176dd320c5c1eb2edcdc74 48443d0bb0d21109c89a100b5ce2c208",
+            "ae212a55399729595dea458bc621ff0e",
+            "0ee1c16bb73f0f4fd19881753c01cdbe",
+        ),
+        (
+            "ab0812724a7f1e342742cbed374d94d1 36c6b8795d45b3819830f2c04491faf0
+             990c62e48b8018b2c3e4a0fa3134cb67 fa83e158c994d961c4cb21095c1bf9",
+            "e1a5668a4d5b66a5f68cc5424ed5982d 12976a08c4426d0ce8a82407c4f48207",
+            "9ae831e743978d3a23527c7128149e3a",
+            "5154ad0d2cb26e01274fc51148491f1b",
+        ),
+    ];
+
+    fn poly1305(key: &str, message: &[u8]) -> [u8; 16] {
+        let mut poly = Poly1305::new(&hex_array(key));
+        poly.update(message);
+        poly.finalize()
+    }
+
+    #[test]
+    fn test_poly1305_aes_paper_vectors() {
+        for (message, key, nonce, tag) in PAPER_VECTORS {
+            let mac = Poly1305Aes::new(&hex_array(key));
+            let nonce = hex_array(nonce);
+            assert_eq!(mac.mac(&nonce, &hex(message)), hex_array(tag));
+            assert_eq!(mac.verify(&nonce, &hex(message), &hex(tag)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_poly1305_rfc8439() {
+        // RFC 8439, section 2.5.2.
+        assert_eq!(
+            poly1305(
+                "85d6be7857556d337f4452fe42d506a8 0103808afb0db2fd4abff6af4149f51b",
+                b"Cryptographic Forum Research Group",
+            ),
+            hex_array("a8061dc1305136c6c22b8baf0c0127a9")
+        );
+    }
+
+    #[test]
+    fn test_poly1305_final_reduction() {
+        // RFC 8439, appendix A.3, test vectors 5, 6 and 8, which exercise
+        // the carries of the final reduction modulo 2^130 - 5.
+        let three = hex_array("03000000000000000000000000000000");
+        assert_eq!(
+            poly1305(&format!("02{}", "00".repeat(31)), &[0xff; 16]),
+            three
+        );
+        assert_eq!(
+            poly1305(
+                &format!("02{}{}", "00".repeat(15), "ff".repeat(16)),
+                &hex("02000000000000000000000000000000"),
+            ),
+            three
+        );
+        let message = hex(&format!(
+            "{}f0{}11{}",
+            "ff".repeat(16),
+            "ff".repeat(15),
+            "00".repeat(15)
+        ));
+        assert_eq!(
+            poly1305(&format!("01{}", "00".repeat(31)), &message),
+            hex_array("05000000000000000000000000000000")
+        );
+    }
+
+    #[test]
+    fn test_poly1305_incremental_update() {
+        let (message, key, nonce, tag) = PAPER_VECTORS[3];
+        let mac = Poly1305Aes::new(&hex_array(key));
+        let message = hex(message);
+        for split in 0..=message.len() {
+            let mut poly = mac.with_nonce(&hex_array(nonce));
+            poly.update(&message[..split]);
+            poly.update(&[]);
+            poly.update(&message[split..]);
+            assert_eq!(poly.finalize(), hex_array(tag));
+        }
+        let mut poly = mac.with_nonce(&hex_array(nonce));
+        for byte in &message {
+            poly.update(std::slice::from_ref(byte));
+        }
+        assert_eq!(poly.verify(&hex(tag)), Ok(()));
+    }
+
+    #[test]
+    fn test_poly1305_aes_rejects_wrong_tag() {
+        let (message, key, nonce, tag) = PAPER_VECTORS[2];
+        let mac = Poly1305Aes::new(&hex_array(key));
+        let nonce = hex_array(nonce);
+        let mut wrong = hex(tag);
+        wrong[15] ^= 0x80;
+        assert_eq!(mac.verify(&nonce, &hex(message), &wrong), Err(Error));
+        assert_eq!(
+            mac.verify(&nonce, &hex(message), &hex(tag)[..8]),
+            Err(Error)
+        );
+        let mut other_nonce = nonce;
+        other_nonce[0] ^= 1;
+        assert_eq!(
+            mac.verify(&other_nonce, &hex(message), &hex(tag)),
+            Err(Error)
+        );
+    }
+}
@@ -0,0 +1,123 @@
+mod common;
+
+#[cfg(test)]
+mod siv_tests {
+    use crate::common::*;
+    use cryptonulz::aead::{Error, Siv};
+    use cryptonulz::aes::*;
+
+    #[test]
+    fn test_siv_rfc5297_deterministic() {
+        // RFC 5297, A.1
+        let siv = Siv::<Aes128>::from_key(&hex_array(
+            "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0 f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+        ));
+        let aad = hex("101112131415161718191a1b1c1d1e1f2021222324252627");
+        let plaintext = hex("112233445566778899aabbccddee");
+        let mut data = plaintext.clone();
+        siv.encrypt(&[&aad], &mut data);
+        assert_eq!(
+            data,
+            hex("85632d07c6e8f37f950acd320a2ecc93 40c02b9690c4dc04daef7f6afe5c")
+        );
+        siv.decrypt(&[&aad], &mut data).unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn test_siv_rfc5297_nonce_based() {
+        // RFC 5297, A.2
+        let siv = Siv::<Aes128>::from_key(&hex_array(
+            "7f7e7d7c7b7a79787776757473727170 404142434445464748494a4b4c4d4e4f",
+        ));
+        let ad1 = hex(
+            "00112233445566778899aabbccddeeff deaddadadeaddadaffeeddccbbaa9988
+             7766554433221100",
+        );
+        let ad2 = hex("102030405060708090a0");
+        let nonce = hex("09f911029d74e35bd84156c5635688c0");
+        let plaintext = hex(
+            "7468697320697320736f6d6520706c61 696e7465787420746f20656e63727970
+             74207573696e67205349562d414553",
+        );
+        let mut data = plaintext.clone();
+        let v = siv.encrypt_detached(&[&ad1, &ad2, &nonce], &mut data);
+        assert_eq!(v.to_vec(), hex("7bdb6e3b432667eb06f4d14bff2fbd0f"));
+        assert_eq!(
+            data,
+            hex(
+                "cb900f2fddbe404326601965c889bf17 dba77ceb094fa663b7a3f748ba8af829
+                 ea64ad544a272e9c485b62a3fd5c0d"
+            )
+        );
+        siv.decrypt_detached(&[&ad1, &ad2, &nonce], &mut data, &v)
+            .unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    // The RFC only has 256 bit key examples, these were cross-checked
+    // against OpenSSL's AES-SIV.
+    #[test]
+    fn test_siv_384_and_512_bit_keys() {
+        let aad: [&[u8]; 2] = [b"table=users", b"column=email"];
+        let siv = Siv::<Aes192>::from_key(&std::array::from_fn(|i| i as u8));
+        let mut data = b"deterministic column value".to_vec();
+        siv.encrypt(&aad, &mut data);
+        assert_eq!(
+            data,
+            hex(
+                "33a8cd2fc138ef70c9ef0013e361d549 138f48ace87c6ae7b636a9d7778e89c2
+                 961bc7bd321973bea4c3"
+            )
+        );
+        siv.decrypt(&aad, &mut data).unwrap();
+        assert_eq!(data, b"deterministic column value");
+
+        let siv = Siv::<Aes256>::from_key(&std::array::from_fn(|i| i as u8));
+        let mut data = b"deterministic column value".to_vec();
+        siv.encrypt(&aad, &mut data);
+        assert_eq!(
+            data,
+            hex(
+                "8e2589d6906bc93398e6f03e225988fc 9246a627910a73d7caa9e4266b2eff0f
+                 70b28f682ca3d85d5561"
+            )
+        );
+        siv.decrypt(&aad, &mut data).unwrap();
+        assert_eq!(data, b"deterministic column value");
+
+        let mut empty = Vec::new();
+        siv.encrypt(&[b"table=users"], &mut empty);
+        assert_eq!(empty, hex("809fee22ed7f87a3dae517f7c6fc0605"));
+        siv.decrypt(&[b"table=users"], &mut empty).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_siv_rejects_tampering() {
+        let siv = Siv::<Aes128>::from_key(&[7; 32]);
+        let mut sealed = b"wrapped data key".to_vec();
+        siv.encrypt(&[b"header", b"nonce"], &mut sealed);
+
+        let mut flipped = sealed.clone();
+        flipped[20] ^= 4;
+        let expected = flipped.clone();
+        assert_eq!(
+            siv.decrypt(&[b"header", b"nonce"], &mut flipped),
+            Err(Error)
+        );
+        assert_eq!(flipped, expected);
+
+        // Components are not just concatenated.
+        let mut reordered = sealed.clone();
+        assert_eq!(
+            siv.decrypt(&[b"nonce", b"header"], &mut reordered),
+            Err(Error)
+        );
+        let mut merged = sealed.clone();
+        assert_eq!(siv.decrypt(&[b"headernonce"], &mut merged), Err(Error));
+
+        let mut short = sealed[..BLOCKSIZE - 1].to_vec();
+        assert_eq!(siv.decrypt(&[b"header", b"nonce"], &mut short), Err(Error));
+    }
+}
@@ -0,0 +1,237 @@
+mod common;
+
+#[cfg(test)]
+mod stream_tests {
+    use std::io::{ErrorKind, Read, Write};
+
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::{Cbc, Ecb};
+    use cryptonulz::stream::{Decryptor, Encryptor};
+
+    const IV: [u8; 16] = [0x24; 16];
+
+    fn message(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 251) as u8).collect()
+    }
+
+    fn cbc() -> Cbc<Aes256> {
+        Cbc::new(Aes256::new(&hex_array(SP800_38A_KEY_256)), &IV)
+    }
+
+    fn encrypt_in_pieces(plaintext: &[u8], piece: usize) -> Vec<u8> {
+        let mut encryptor = Encryptor::new(Vec::new(), cbc());
+        for chunk in plaintext.chunks(piece) {
+            encryptor.write_all(chunk).unwrap();
+        }
+        encryptor.finish().unwrap()
+    }
+
+    #[test]
+    fn test_encryptor_matches_cbc() {
+        for len in [0, 1, 15, 16, 17, 100, 8191, 8192, 8193, 20_000] {
+            let plaintext = message(len);
+            let mut expected = plaintext.clone();
+            cbc().encrypt(&mut expected);
+            for piece in [1, 5, 16, 33, 8192, 100_000] {
+                assert_eq!(
+                    encrypt_in_pieces(&plaintext, piece),
+                    expected,
+                    "len {len}, pieces of {piece}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_encryptor_pads_only_on_finish() {
+        let mut encryptor = Encryptor::new(Vec::new(), cbc());
+        encryptor.write_all(&message(40)).unwrap();
+        encryptor.flush().unwrap();
+        assert_eq!(encryptor.get_ref().len(), 32);
+        let ciphertext = encryptor.finish().unwrap();
+        assert_eq!(ciphertext.len(), 48);
+
+        // An empty stream still gets a block of PKCS#7 padding.
+        let ciphertext = Encryptor::new(Vec::new(), cbc()).finish().unwrap();
+        let mut expected = Vec::new();
+        cbc().encrypt(&mut expected);
+        assert_eq!(ciphertext, expected);
+        assert_eq!(ciphertext.len(), 16);
+    }
+
+    #[test]
+    fn test_encryptor_ecb_matches_cryptoprovider() {
+        let key = hex_array(SP800_38A_KEY_128);
+        let plaintext = message(1000);
+        let mut encryptor = Encryptor::new(Vec::new(), Ecb::new(Aes128::new(&key)));
+        encryptor.write_all(&plaintext).unwrap();
+        let ciphertext = encryptor.finish().unwrap();
+
+        let mut expected = plaintext.clone();
+        Aes128::new(&key).encrypt(&mut expected);
+        assert_eq!(ciphertext, expected);
+    }
+
+    #[test]
+    fn test_encryptor_large_stream() {
+        let plaintext = message(3 * 1024 * 1024 + 5);
+        let mut expected = plaintext.clone();
+        cbc().encrypt(&mut expected);
+        let mut encryptor = Encryptor::new(Vec::new(), cbc());
+        std::io::copy(&mut &plaintext[..], &mut encryptor).unwrap();
+        assert_eq!(encryptor.finish().unwrap(), expected);
+    }
+
+    /// Hands out at most `max` bytes per read, like a socket or pipe.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        max: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.max).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn decrypt_all(ciphertext: &[u8], max: usize) -> std::io::Result<Vec<u8>> {
+        let reader = Trickle {
+            data: ciphertext,
+            max,
+        };
+        let mut plaintext = Vec::new();
+        Decryptor::new(reader, cbc()).read_to_end(&mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    #[test]
+    fn test_decryptor_round_trip() {
+        for len in [0, 1, 15, 16, 17, 100, 8191, 8192, 8193, 20_000] {
+            let plaintext = message(len);
+            let ciphertext = encrypt_in_pieces(&plaintext, 8192);
+            for max in [1, 7, 16, 17, 8192, 100_000] {
+                assert_eq!(
+                    decrypt_all(&ciphertext, max).unwrap(),
+                    plaintext,
+                    "len {len}, reads of {max}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decryptor_small_reads() {
+        let plaintext = message(1000);
+        let ciphertext = encrypt_in_pieces(&plaintext, 1000);
+        let mut decryptor = Decryptor::new(&ciphertext[..], cbc());
+        let mut decrypted = Vec::new();
+        let mut buf = [0; 3];
+        loop {
+            let n = decryptor.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            decrypted.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_decryptor_ecb_large_stream() {
+        let key = hex_array(SP800_38A_KEY_192);
+        let plaintext = message(3 * 1024 * 1024 + 5);
+        let mut ciphertext = plaintext.clone();
+        Aes192::new(&key).encrypt(&mut ciphertext);
+        let mut decryptor = Decryptor::new(&ciphertext[..], Ecb::new(Aes192::new(&key)));
+        let mut decrypted = Vec::new();
+        decryptor.read_to_end(&mut decrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_decryptor_rejects_invalid_padding() {
+        let plaintext = message(100);
+        let mut ciphertext = encrypt_in_pieces(&plaintext, 100);
+        // Flipping a bit in the second to last block flips the same bit of
+        // the last padding byte.
+        let len = ciphertext.len();
+        ciphertext[len - 17] ^= 0x40;
+        let err = decrypt_all(&ciphertext, 8192).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        // Padding bytes that disagree with the padding length.
+        let mut ciphertext = encrypt_in_pieces(&plaintext, 100);
+        ciphertext[len - 18] ^= 0x01;
+        let mut decryptor = Decryptor::new(&ciphertext[..], cbc());
+        let mut decrypted = Vec::new();
+        let err = decryptor.read_to_end(&mut decrypted).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(decrypted.len() < plaintext.len());
+        // The error sticks instead of turning into a silent EOF.
+        assert_eq!(
+            decryptor.read(&mut [0; 16]).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_decryptor_rejects_truncated_ciphertext() {
+        let ciphertext = encrypt_in_pieces(&message(100), 100);
+        let err = decrypt_all(&ciphertext[..ciphertext.len() - 1], 8192).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        // PKCS#7 always adds a block, so an empty ciphertext is invalid too.
+        let err = decrypt_all(&[], 8192).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    /// Fails the first write with `WouldBlock`, which callers may retry.
+    struct FailOnce {
+        written: Vec<u8>,
+        failed: bool,
+    }
+
+    impl Write for FailOnce {
+        fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+            if !self.failed {
+                self.failed = true;
+                return Err(ErrorKind::WouldBlock.into());
+            }
+            self.written.extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn fail_once() -> FailOnce {
+        FailOnce {
+            written: Vec::new(),
+            failed: false,
+        }
+    }
+
+    #[test]
+    fn test_encryptor_fails_after_inner_write_error() {
+        let plaintext = message(40);
+        let mut encryptor = Encryptor::new(fail_once(), cbc());
+        let err = encryptor.write_all(&plaintext).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WouldBlock);
+        // The blocks of the failed write are already encrypted, retrying must
+        // not encrypt them a second time.
+        assert!(encryptor.write_all(&plaintext).is_err());
+        assert!(encryptor.flush().is_err());
+        assert!(encryptor.get_ref().written.is_empty());
+        assert!(encryptor.finish().is_err());
+
+        // The padded last block is written by finish.
+        let mut encryptor = Encryptor::new(fail_once(), cbc());
+        encryptor.write_all(&plaintext[..10]).unwrap();
+        assert!(encryptor.finish().is_err());
+    }
+}
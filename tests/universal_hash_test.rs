@@ -0,0 +1,78 @@
+mod common;
+
+#[cfg(test)]
+mod universal_hash_tests {
+    use crate::common::*;
+    use cryptonulz::universal_hash::{Ghash, Polyval, UniversalHash};
+
+    fn hash<U: UniversalHash>(key: &str, data: &str) -> Vec<u8> {
+        let mut hash = U::new(&hex_array(key));
+        hash.update_padded(&hex(data));
+        hash.finalize().to_vec()
+    }
+
+    #[test]
+    fn test_ghash_gcm_test_case_2() {
+        // McGrew and Viega, GCM test case 2: GHASH(H, {}, C).
+        assert_eq!(
+            hash::<Ghash>(
+                "66e94bd4ef8a2c3b884cfa59ca342b2e",
+                "0388dace60b6a392f328c2b971b2fe78 00000000000000000000000000000080",
+            ),
+            hex("f38cbb1ad69223dcc3457ae5b6b0f885")
+        );
+    }
+
+    #[test]
+    fn test_polyval_rfc8452_vectors() {
+        // RFC 8452, Appendix A.
+        assert_eq!(
+            hash::<Polyval>(
+                "25629347589242761d31f826ba4b757b",
+                "4f4f95668c83dfb6401762bb2d01a262 d1a24ddd2721d006bbe45f20d3c9f362",
+            ),
+            hex("f7a3b47b846119fae5b7866cf5e5b77e")
+        );
+        // RFC 8452, Appendix C.1, the first AEAD_AES_128_GCM_SIV example.
+        assert_eq!(
+            hash::<Polyval>(
+                "d9b360279694941ac5dbc6987ada7377",
+                "01000000000000000000000000000000 00000000000000004000000000000000",
+            ),
+            hex("eb93b7740962c5e49d2a90a7dc5cec74")
+        );
+    }
+
+    fn check_padding_and_clone<U: UniversalHash + Clone>() {
+        let key = [0x5c; 16];
+        let data: Vec<u8> = (0..40).collect();
+
+        let mut padded = U::new(&key);
+        padded.update_padded(&data);
+        let mut blocks = U::new(&key);
+        blocks.update_block(data[..16].try_into().unwrap());
+        blocks.update_block(data[16..32].try_into().unwrap());
+        let mut last = [0; 16];
+        last[..8].copy_from_slice(&data[32..]);
+        let prefix = blocks.clone();
+        blocks.update_block(&last);
+        assert_eq!(padded.finalize(), blocks.finalize());
+
+        // A clone continues independently from the shared prefix.
+        let mut other = prefix.clone();
+        other.update_block(&[1; 16]);
+        assert_ne!(prefix.finalize(), other.finalize());
+    }
+
+    #[test]
+    fn test_update_padded_matches_blocks() {
+        check_padding_and_clone::<Ghash>();
+        check_padding_and_clone::<Polyval>();
+    }
+
+    #[test]
+    fn test_zero_key_hashes_to_zero() {
+        assert_eq!(hash::<Ghash>(&"00".repeat(16), "0123456789"), [0; 16]);
+        assert_eq!(hash::<Polyval>(&"00".repeat(16), "0123456789"), [0; 16]);
+    }
+}
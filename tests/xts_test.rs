@@ -0,0 +1,181 @@
+mod common;
+
+#[cfg(test)]
+mod xts_tests {
+    use crate::common::*;
+    use cryptonulz::aes::*;
+    use cryptonulz::modes::Xts;
+
+    // Vectors from IEEE 1619-2007, Annex B.
+    fn check<C: Cryptoprovider>(xts: &Xts<C>, sector: u128, plaintext: &[u8], ciphertext: &str) {
+        let mut data = plaintext.to_vec();
+        xts.encrypt_sector(sector, &mut data);
+        assert_eq!(data, hex(ciphertext));
+        xts.decrypt_sector(sector, &mut data);
+        assert_eq!(data, plaintext);
+    }
+
+    fn counting(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn test_xts_aes128_vectors_2_and_3() {
+        let xts = Xts::new(Aes128::new(&[0x11; 16]), Aes128::new(&[0x22; 16]));
+        check(
+            &xts,
+            0x3333333333,
+            &[0x44; 32],
+            "c454185e6a16936e39334038acef838b fb186fff7480adc4289382ecd6d394f0",
+        );
+        let xts = Xts::new(
+            Aes128::new(&hex_array("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0")),
+            Aes128::new(&[0x22; 16]),
+        );
+        check(
+            &xts,
+            0x3333333333,
+            &[0x44; 32],
+            "af85336b597afc1a900b2eb21ec949d2 92df4c047e0b21532186a5971a227a89",
+        );
+    }
+
+    #[test]
+    fn test_xts_aes128_vector_4() {
+        let xts = Xts::new(
+            Aes128::new(&hex_array("27182818284590452353602874713526")),
+            Aes128::new(&hex_array("31415926535897932384626433832795")),
+        );
+        check(
+            &xts,
+            0,
+            &[counting(256), counting(256)].concat(),
+            "27a7479befa1d476489f308cd4cfa6e2 a96e4bbe3208ff25287dd3819616e89c
+             c78cf7f5e543445f8333d8fa7f560000 05279fa5d8b5e4ad40e736ddb4d35412
+             328063fd2aab53e5ea1e0a9f332500a5 df9487d07a5c92cc512c8866c7e860ce
+             93fdf166a24912b422976146ae20ce84 6bb7dc9ba94a767aaef20c0d61ad0265
+             5ea92dc4c4e41a8952c651d33174be51 a10c421110e6d81588ede82103a252d8
+             a750e8768defffed9122810aaeb99f91 72af82b604dc4b8e51bcb08235a6f434
+             1332e4ca60482a4ba1a03b3e65008fc5 da76b70bf1690db4eae29c5f1badd03c
+             5ccf2a55d705ddcd86d449511ceb7ec3 0bf12b1fa35b913f9f747a8afd1b130e
+             94bff94effd01a91735ca1726acd0b19 7c4e5b03393697e126826fb6bbde8ecc
+             1e08298516e2c9ed03ff3c1b7860f6de 76d4cecd94c8119855ef5297ca67e9f3
+             e7ff72b1e99785ca0a7e7720c5b36dc6 d72cac9574c8cbbc2f801e23e56fd344
+             b07f22154beba0f08ce8891e643ed995 c94d9a69c9f1b5f499027a78572aeebd
+             74d20cc39881c213ee770b1010e4bea7 18846977ae119f7a023ab58cca0ad752
+             afe656bb3c17256a9f6e9bf19fdd5a38 fc82bbe872c5539edb609ef4f79c203e
+             bb140f2e583cb2ad15b4aa5b655016a8 449277dbd477ef2c8d6c017db738b18d
+             eb4a427d1923ce3ff262735779a418f2 0a282df920147beabe421ee5319d0568",
+        );
+    }
+
+    #[test]
+    fn test_xts_aes256_vector_10() {
+        let xts = Xts::new(
+            Aes256::new(&hex_array(
+                "27182818284590452353602874713526 62497757247093699959574966967627",
+            )),
+            Aes256::new(&hex_array(
+                "31415926535897932384626433832795 02884197169399375105820974944592",
+            )),
+        );
+        check(
+            &xts,
+            0xff,
+            &[counting(256), counting(256)].concat(),
+            "1c3b3a102f770386e4836c99e370cf9b ea00803f5e482357a4ae12d414a3e63b
+             5d31e276f8fe4a8d66b317f9ac683f44 680a86ac35adfc3345befecb4bb188fd
+             5776926c49a3095eb108fd1098baec70 aaa66999a72a82f27d848b21d4a741b0
+             c5cd4d5fff9dac89aeba122961d03a75 7123e9870f8acf1000020887891429ca
+             2a3e7a7d7df7b10355165c8b9a6d0a7d e8b062c4500dc4cd120c0f7418dae3d0
+             b5781c34803fa75421c790dfe1de1834 f280d7667b327f6c8cd7557e12ac3a0f
+             93ec05c52e0493ef31a12d3d9260f79a 289d6a379bc70c50841473d1a8cc81ec
+             583e9645e07b8d9670655ba5bbcfecc6 dc3966380ad8fecb17b6ba02469a020a
+             84e18e8f84252070c13e9f1f289be54f bc481457778f616015e1327a02b140f1
+             505eb309326d68378f8374595c849d84 f4c333ec4423885143cb47bd71c5edae
+             9be69a2ffeceb1bec9de244fbe15992b 11b77c040f12bd8f6a975a44a0f90c29
+             a9abc3d4d893927284c58754cce29452 9f8614dcd2aba991925fedc4ae74ffac
+             6e333b93eb4aff0479da9a410e4450e0 dd7ae4c6e2910900575da401fc07059f
+             645e8b7e9bfdef33943054ff84011493 c27b3429eaedb4ed5376441a77ed4385
+             1ad77f16f541dfd269d50d6a5f14fb0a ab1cbb4c1550be97f7ab4066193c4caa
+             773dad38014bd2092fa755c824bb5e54 c4f36ffda9fcea70b9c6e693e148c151",
+        );
+    }
+
+    #[test]
+    fn test_xts_aes128_ciphertext_stealing_vectors_15_to_18() {
+        let xts = Xts::new(
+            Aes128::new(&hex_array("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0")),
+            Aes128::new(&hex_array("bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0")),
+        );
+        let sector = 0x123456789a;
+        check(
+            &xts,
+            sector,
+            &counting(17),
+            "6c1625db4671522d3d7599601de7ca09 ed",
+        );
+        check(
+            &xts,
+            sector,
+            &counting(18),
+            "d069444b7a7e0cab09e24447d24deb1f edbf",
+        );
+        check(
+            &xts,
+            sector,
+            &counting(19),
+            "e5df1351c0544ba1350b3363cd8ef4be edbf9d",
+        );
+        check(
+            &xts,
+            sector,
+            &counting(20),
+            "9d84c813f719aa2c7be3f66171c7c5c2 edbf9dac",
+        );
+    }
+
+    #[test]
+    fn test_xts_bulk_sectors() {
+        let xts = Xts::new(
+            Aes128::new(&hex_array("27182818284590452353602874713526")),
+            Aes128::new(&hex_array("31415926535897932384626433832795")),
+        );
+        let sector_size = 40;
+        let plaintext = counting(4 * sector_size);
+        let mut bulk = plaintext.clone();
+        xts.encrypt_sectors(7, sector_size, &mut bulk);
+
+        let mut single = plaintext.clone();
+        for (i, chunk) in single.chunks_mut(sector_size).enumerate() {
+            xts.encrypt_sector(7 + i as u128, chunk);
+        }
+        assert_eq!(bulk, single);
+        assert_ne!(bulk[..sector_size], bulk[sector_size..2 * sector_size]);
+
+        xts.decrypt_sectors(7, sector_size, &mut bulk);
+        assert_eq!(bulk, plaintext);
+    }
+
+    // Vector 1 uses the same all zero key for both halves, which the
+    // standard itself forbids.
+    #[test]
+    #[should_panic(expected = "XTS needs two different keys")]
+    fn test_xts_rejects_identical_keys() {
+        Xts::new(Aes128::new(&[0; 16]), Aes128::new(&[0; 16]));
+    }
+
+    #[test]
+    #[should_panic(expected = "XTS sectors have to hold at least one block")]
+    fn test_xts_rejects_zero_sector_size() {
+        let xts = Xts::new(Aes128::new(&[0x11; 16]), Aes128::new(&[0x22; 16]));
+        xts.encrypt_sectors(0, 0, &mut []);
+    }
+
+    #[test]
+    #[should_panic(expected = "XTS sectors have to hold at least one block")]
+    fn test_xts_rejects_short_sector_size() {
+        let xts = Xts::new(Aes128::new(&[0x11; 16]), Aes128::new(&[0x22; 16]));
+        xts.decrypt_sectors(0, 8, &mut [0; 32]);
+    }
+}